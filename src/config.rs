@@ -0,0 +1,200 @@
+/*
+ * Copyright 2021-2023 Golden_Water
+ * SPDX-License-Identifier: AGPL-3.0-only
+ */
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use log::{info, warn};
+use serde::Deserialize;
+
+use crate::utils::match_path;
+use crate::TEResult;
+
+pub const CONFIG_FILE_NAME: &str = "type_exporter.toml";
+
+/// project wide settings, read from `type_exporter.toml` in the root of the cargo project
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+  /// how maps are exported
+  pub map_style: MapStyle,
+  /// how 64-bit and 128-bit integers are exported,
+  /// can be overridden per field with `#[te(big_int = "...")]`
+  pub big_int: BigIntStyle,
+  /// map well-known third-party types like `chrono::DateTime` or `uuid::Uuid`, and serde helpers
+  /// like `chrono::serde::ts_seconds` or `serde_with::DisplayFromStr`,
+  /// to their serde json representation
+  pub builtin_types: bool,
+  /// export a type for serializing and one with the `Input` suffix for deserializing
+  /// (e.g. `User` and `UserInput`) instead of a single type for both
+  pub split_directions: bool,
+  /// how enums serialized as their discriminant by serde_repr are exported
+  pub numeric_enum: NumericEnumStyle,
+  /// the member added to the union of an enum that may get values not known yet,
+  /// e.g. `"unknown"`, by default it's `(string & {})` in place of the tag,
+  /// see [EnumInfo::is_open](crate::item::enum_info::EnumInfo::is_open)
+  pub enum_fallback: Option<String>,
+  /// types that aren't scanned, by rust path, e.g.
+  /// `"foreign::Money" = "string"` or `"geo::Point" = { type = "Point", import = "@/geo" }`
+  pub types: BTreeMap<String, TypeMapping>,
+  /// the json representation of fields using `#[serde(with = "...")]`, `serialize_with`,
+  /// `deserialize_with` or `#[serde_as(as = "...")]`, by the path of the module, function or
+  /// `serde_as` type, e.g. `"my_serde::as_string" = "string"`
+  pub with: BTreeMap<String, TypeMapping>,
+  /// enabled features, for `#[cfg(feature = "...")]` and `#[cfg_attr(feature = "...", ...)]`
+  pub features: Vec<String>,
+  /// other enabled cfg options, written like `--cfg` of rustc, e.g. `"unix"` or
+  /// `'target_os = "linux"'`
+  pub cfg: Vec<String>,
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Self {
+      map_style: Default::default(),
+      big_int: Default::default(),
+      builtin_types: true,
+      split_directions: false,
+      numeric_enum: Default::default(),
+      enum_fallback: None,
+      types: BTreeMap::new(),
+      with: BTreeMap::new(),
+      features: vec![],
+      cfg: vec![],
+    }
+  }
+}
+
+impl Config {
+  /// load the config file in the root if there is one, otherwise use the default config
+  pub fn load(root: &Path) -> TEResult<Self> {
+    let config_file = root.join(CONFIG_FILE_NAME);
+
+    if !config_file.exists() {
+      return Ok(Self::default());
+    }
+
+    info!("loading config from {config_file:?}");
+    Ok(toml::from_str(&fs::read_to_string(config_file)?)?)
+  }
+
+  /// find the mapping by the path written in the source,
+  /// both `Money` and `money::Money` matches `foreign::money::Money`
+  pub fn find_type_mapping(&self, path: &[String]) -> Option<&TypeMapping> {
+    find_mapping(&self.types, path)
+  }
+
+  /// find the mapping of `with` or `serde_as`, matched like [Config::find_type_mapping]
+  pub fn find_with_mapping(&self, path: &[String]) -> Option<&TypeMapping> {
+    find_mapping(&self.with, path)
+  }
+
+  /// whether `#[cfg(name)]` or `#[cfg(name = "value")]` holds
+  pub fn is_cfg_set(&self, name: &str, value: Option<&str>) -> bool {
+    if let ("feature", Some(value)) = (name, value) {
+      return self.features.iter().any(|it| it == value);
+    }
+
+    self.cfg.iter().any(|it| {
+      let (it_name, it_value) = match it.split_once('=') {
+        Some((it_name, it_value)) => (it_name, Some(it_value.trim().trim_matches('"'))),
+        None => (it.as_str(), None),
+      };
+      it_name.trim() == name && it_value == value
+    })
+  }
+}
+
+/// an exact match if there is one, otherwise the key with the fewest segments,
+/// keys that are equally close are ambiguous and the first one is used
+fn find_mapping<'a>(
+  table: &'a BTreeMap<String, TypeMapping>,
+  path: &[String],
+) -> Option<&'a TypeMapping> {
+  let mut matches = table
+    .iter()
+    .filter(|(key, _)| match_path(key, path))
+    .map(|(key, mapping)| (key.split("::").count(), key, mapping))
+    .collect::<Vec<_>>();
+  matches.sort_by_key(|(len, ..)| *len);
+
+  let (len, key, mapping) = *matches.first()?;
+  let ambiguous = matches
+    .iter()
+    .filter(|it| it.0 == len)
+    .map(|it| it.1.as_str())
+    .collect::<Vec<_>>();
+  if ambiguous.len() > 1 {
+    warn!(
+      "{} is ambiguous between {}, using {key}",
+      path.join("::"),
+      ambiguous.join(", ")
+    );
+  }
+
+  Some(mapping)
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum TypeMapping {
+  Type(String),
+  Import {
+    #[serde(rename = "type")]
+    ty: String,
+    import: Option<String>,
+  },
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MapStyle {
+  /// the shape serde_json produces, maps are objects with string keys:
+  /// `Record<string, V>`, ``Record<`${number}`, V>`` for integer keys
+  /// and `Partial<Record<K, V>>` for keys of user defined types (e.g. unit enums)
+  #[default]
+  Json,
+  /// `Map<K, V>`, for maps deserialized with a custom deserializer
+  EsMap,
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NumericEnumStyle {
+  /// `type Level = 1 | 10`
+  #[default]
+  Union,
+  /// `enum Level { Low = 1, High = 10 }`
+  Enum,
+  /// `const enum Level { Low = 1, High = 10 }`
+  ConstEnum,
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BigIntStyle {
+  /// what serde_json produces by default
+  #[default]
+  Number,
+  /// for a custom deserializer that produces `bigint`
+  BigInt,
+  /// serialized through `Display`/`FromStr`, e.g. `serde_with::DisplayFromStr`
+  String,
+}
+
+impl FromStr for BigIntStyle {
+  type Err = ();
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "number" => Ok(Self::Number),
+      "bigint" => Ok(Self::BigInt),
+      "string" => Ok(Self::String),
+      _ => Err(()),
+    }
+  }
+}
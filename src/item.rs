@@ -1,7 +1,12 @@
+pub mod alias_info;
 pub mod attribute_info;
+pub mod cfg;
 pub mod enum_info;
 pub mod field_info;
 pub mod item_info;
 pub mod item_parser;
+pub mod parse_context;
+pub mod proxy_info;
 pub mod struct_info;
 pub mod type_info;
+pub mod with_info;
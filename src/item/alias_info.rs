@@ -0,0 +1,55 @@
+/*
+ * Copyright 2021-2023 Golden_Water
+ * SPDX-License-Identifier: AGPL-3.0-only
+ */
+
+use swc_core::ecma::ast;
+use syn::ItemType;
+
+use crate::item::attribute_info::{parse_attributes, AttributeInfo, Direction};
+use crate::item::parse_context::{parse_generics, ParseContext};
+use crate::item::type_info::TypeInfo;
+use crate::utils::rename_name;
+use crate::utils::ts_ast_utils::{
+  create_ident, create_module_decl_item, create_type_alias_decl, create_type_param_decl,
+};
+use crate::{TEResult, TsAst};
+
+#[derive(Debug, Clone)]
+pub struct AliasInfo {
+  pub name: String,
+  pub generics: Vec<String>,
+  pub ty: TypeInfo,
+  pub attr: AttributeInfo,
+}
+
+impl AliasInfo {
+  pub fn parse_item_type(ctx: &ParseContext, item_type: &ItemType) -> TEResult<Self> {
+    let generics = parse_generics(&item_type.generics);
+    let ctx = ctx.clone().with_generics(generics.clone());
+    let attr = parse_attributes(&ctx, &item_type.attrs)?;
+
+    Ok(Self {
+      name: item_type.ident.to_string(),
+      generics,
+      ty: TypeInfo::parse_type(&ctx, &item_type.ty, &attr, &item_type.ty)?,
+      attr,
+    })
+  }
+
+  pub fn to_ts_ast(&self, direction: Direction) -> TsAst<ast::ModuleItem> {
+    let ts_ast = self.ty.to_ts_ast(direction);
+    let name = rename_name(&self.attr, None, Some(&self.name), direction).unwrap();
+
+    let decl = create_type_alias_decl(
+      create_ident(direction.type_name(&name)),
+      create_type_param_decl(&self.generics),
+      *ts_ast.ast.type_ann,
+    );
+
+    TsAst {
+      ast: create_module_decl_item(decl),
+      dependencies: ts_ast.dependencies,
+    }
+  }
+}
@@ -3,34 +3,133 @@
  * SPDX-License-Identifier: AGPL-3.0-only
  */
 
+use std::fmt::Display;
 use std::str::FromStr;
 
-use heck::{
-  ToKebabCase, ToLowerCamelCase, ToShoutyKebabCase, ToShoutySnakeCase, ToSnakeCase,
-  ToUpperCamelCase,
-};
-use syn::parse::{Parse, ParseStream};
-use syn::punctuated::Punctuated;
-use syn::{Attribute, Ident, LitStr, Meta, Token};
-
+use log::warn;
+use proc_macro2::{Group, Span};
+use syn::__private::ToTokens;
+use syn::meta::ParseNestedMeta;
+use syn::parse::Parser;
+use syn::spanned::Spanned;
+use syn::token::Paren;
+use syn::{Attribute, Expr, ExprLit, Lit, Meta, Token};
+
+use crate::config::BigIntStyle;
+use crate::item::cfg::expand_cfg_attr;
+use crate::item::parse_context::ParseContext;
 use crate::{TEError, TEResult};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct AttributeInfo {
   pub entry: AttributeInfoItem<String>,
+  /// `#[derive(Serialize_repr)]`
+  pub serialize_repr: AttributeInfoItem<String>,
+  /// `#[derive(Deserialize_repr)]`
+  pub deserialize_repr: AttributeInfoItem<String>,
 
   pub retype: AttributeInfoItem<String>,
+  pub big_int: AttributeInfoItem<BigIntStyle>,
   pub rename: AttributeInfoItem<String>,
+  pub rename_serialize: AttributeInfoItem<String>,
+  pub rename_deserialize: AttributeInfoItem<String>,
   pub rename_all: AttributeInfoItem<RenameAll>,
+  pub rename_all_serialize: AttributeInfoItem<RenameAll>,
+  pub rename_all_deserialize: AttributeInfoItem<RenameAll>,
+  pub rename_all_fields: AttributeInfoItem<RenameAll>,
+  pub rename_all_fields_serialize: AttributeInfoItem<RenameAll>,
+  pub rename_all_fields_deserialize: AttributeInfoItem<RenameAll>,
+  pub alias: Vec<String>,
   pub tag: AttributeInfoItem<String>,
   pub tag_content: AttributeInfoItem<String>,
+  pub untagged: AttributeInfoItem<String>,
+  pub other: AttributeInfoItem<String>,
+  /// `#[non_exhaustive]`
+  pub non_exhaustive: AttributeInfoItem<String>,
+  pub transparent: AttributeInfoItem<String>,
+  pub from: AttributeInfoItem<String>,
+  pub try_from: AttributeInfoItem<String>,
+  pub into: AttributeInfoItem<String>,
+  pub with: AttributeInfoItem<String>,
+  pub serialize_with: AttributeInfoItem<String>,
+  pub deserialize_with: AttributeInfoItem<String>,
+  /// `#[serde_as(as = "...")]`
+  pub serde_as: AttributeInfoItem<String>,
   pub skip: AttributeInfoItem<String>,
+  pub flatten: AttributeInfoItem<String>,
   pub skip_serializing: AttributeInfoItem<String>,
+  pub skip_serializing_if: AttributeInfoItem<String>,
+  pub skip_deserializing: AttributeInfoItem<String>,
+  pub default: AttributeInfoItem<String>,
 }
 
 impl AttributeInfo {
-  pub fn is_skipped(&self) -> bool {
-    self.skip.is_set() || self.skip_serializing.is_set()
+  pub fn rename(&self, direction: Direction) -> Option<&String> {
+    match direction {
+      Direction::Both | Direction::Serialize => self.rename_serialize.get(),
+      Direction::Deserialize => self.rename_deserialize.get(),
+    }
+    .or(self.rename.get())
+  }
+
+  pub fn rename_all(&self, direction: Direction) -> Option<&RenameAll> {
+    match direction {
+      Direction::Both | Direction::Serialize => self.rename_all_serialize.get(),
+      Direction::Deserialize => self.rename_all_deserialize.get(),
+    }
+    .or(self.rename_all.get())
+  }
+
+  /// the `rename_all` for the fields of every struct variant of an enum
+  pub fn rename_all_fields(&self, direction: Direction) -> Option<&RenameAll> {
+    match direction {
+      Direction::Both | Direction::Serialize => self.rename_all_fields_serialize.get(),
+      Direction::Deserialize => self.rename_all_fields_deserialize.get(),
+    }
+    .or(self.rename_all_fields.get())
+  }
+
+  pub fn is_skipped(&self, direction: Direction) -> bool {
+    self.skip.is_set()
+      || match direction {
+        Direction::Both | Direction::Serialize => self.skip_serializing.is_set(),
+        Direction::Deserialize => self.skip_deserializing.is_set(),
+      }
+  }
+
+  /// whether a field may be absent from the json, `container` is the attributes of the struct
+  ///
+  /// - serializing: the field is absent when `skip_serializing_if` is set
+  /// - deserializing: the field may be omitted when `default` is set on it or on the container
+  ///
+  /// with [Direction::Both] the type is shared by both directions,
+  /// so the field is optional if either applies
+  pub fn is_optional(&self, container: &AttributeInfo, direction: Direction) -> bool {
+    let serialize = self.skip_serializing_if.is_set();
+    let deserialize = self.default.is_set() || container.default.is_set();
+
+    match direction {
+      Direction::Both => serialize || deserialize,
+      Direction::Serialize => serialize,
+      Direction::Deserialize => deserialize,
+    }
+  }
+
+  /// whether the enum is serialized as its discriminant,
+  /// [Direction::Both] is the serialized form
+  pub fn is_repr(&self, direction: Direction) -> bool {
+    match direction {
+      Direction::Both | Direction::Serialize => self.serialize_repr.is_set(),
+      Direction::Deserialize => self.deserialize_repr.is_set(),
+    }
+  }
+
+  /// whether it's converted by a serde helper, see [WithInfo](crate::item::with_info::WithInfo)
+  pub fn is_with(&self) -> bool {
+    self.with.is_set()
+      || self.serialize_with.is_set()
+      || self.deserialize_with.is_set()
+      || self.serde_as.is_set()
   }
 
   pub fn is_entry(&self) -> bool {
@@ -38,6 +137,30 @@ impl AttributeInfo {
   }
 }
 
+/// the side of serde an exported type describes
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum Direction {
+  /// a single type used for both
+  #[default]
+  Both,
+  /// the json produced by `Serialize`
+  Serialize,
+  /// the json accepted by `Deserialize`, exported with the [INPUT_TYPE_SUFFIX]
+  Deserialize,
+}
+
+pub const INPUT_TYPE_SUFFIX: &str = "Input";
+
+impl Direction {
+  /// name of the exported type for the type named `name`
+  pub fn type_name(&self, name: &str) -> String {
+    match self {
+      Direction::Both | Direction::Serialize => name.to_string(),
+      Direction::Deserialize => format!("{name}{INPUT_TYPE_SUFFIX}"),
+    }
+  }
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub enum AttributeInfoItem<T> {
   #[default]
@@ -101,22 +224,72 @@ pub enum RenameAll {
   ScreamingKebabCase,
 }
 
+/// the conversions of serde_derive, which expects variants in PascalCase and fields in snake_case
 impl RenameAll {
-  pub fn do_convert<S: AsRef<str>>(&self, string: S) -> String {
-    let string = string.as_ref();
+  pub fn apply_to_variant(&self, variant: &str) -> String {
     match self {
-      RenameAll::LowerCase => string.to_lowercase(),
-      RenameAll::UpperCase => string.to_uppercase(),
-      RenameAll::PascalCase => string.to_upper_camel_case(),
-      RenameAll::CamelCase => string.to_lower_camel_case(),
-      RenameAll::SnakeCase => string.to_snake_case(),
-      RenameAll::ScreamingSnakeCase => string.to_shouty_snake_case(),
-      RenameAll::KebabCase => string.to_kebab_case(),
-      RenameAll::ScreamingKebabCase => string.to_shouty_kebab_case(),
+      RenameAll::PascalCase => variant.to_string(),
+      RenameAll::LowerCase => variant.to_ascii_lowercase(),
+      RenameAll::UpperCase => variant.to_ascii_uppercase(),
+      RenameAll::CamelCase => lowercase_first(variant),
+      RenameAll::SnakeCase => {
+        let mut snake = String::new();
+        for (i, ch) in variant.char_indices() {
+          if i > 0 && ch.is_uppercase() {
+            snake.push('_');
+          }
+          snake.push(ch.to_ascii_lowercase());
+        }
+        snake
+      }
+      RenameAll::ScreamingSnakeCase => RenameAll::SnakeCase
+        .apply_to_variant(variant)
+        .to_ascii_uppercase(),
+      RenameAll::KebabCase => RenameAll::SnakeCase
+        .apply_to_variant(variant)
+        .replace('_', "-"),
+      RenameAll::ScreamingKebabCase => RenameAll::ScreamingSnakeCase
+        .apply_to_variant(variant)
+        .replace('_', "-"),
+    }
+  }
+
+  pub fn apply_to_field(&self, field: &str) -> String {
+    match self {
+      RenameAll::LowerCase | RenameAll::SnakeCase => field.to_string(),
+      RenameAll::UpperCase | RenameAll::ScreamingSnakeCase => field.to_ascii_uppercase(),
+      RenameAll::PascalCase => {
+        let mut pascal = String::new();
+        let mut capitalize = true;
+        for ch in field.chars() {
+          if ch == '_' {
+            capitalize = true;
+          } else if capitalize {
+            pascal.push(ch.to_ascii_uppercase());
+            capitalize = false;
+          } else {
+            pascal.push(ch);
+          }
+        }
+        pascal
+      }
+      RenameAll::CamelCase => lowercase_first(&RenameAll::PascalCase.apply_to_field(field)),
+      RenameAll::KebabCase => field.replace('_', "-"),
+      RenameAll::ScreamingKebabCase => RenameAll::ScreamingSnakeCase
+        .apply_to_field(field)
+        .replace('_', "-"),
     }
   }
 }
 
+fn lowercase_first(string: &str) -> String {
+  let mut chars = string.chars();
+  match chars.next() {
+    Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+    None => String::new(),
+  }
+}
+
 impl FromStr for RenameAll {
   type Err = ();
 
@@ -135,109 +308,377 @@ impl FromStr for RenameAll {
   }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+/// a `key`, `key = value` or `key(nested, ...)` inside an attribute
+#[derive(Clone, Debug)]
 struct Arg {
-  ident: Ident,
-  value: Option<LitStr>,
+  key: String,
+  /// string literals are unquoted, other values are kept as tokens
+  value: Option<String>,
+  /// e.g. `rename(serialize = "...", deserialize = "...")`
+  nested: Vec<Arg>,
+  span: Span,
 }
 
-impl Parse for Arg {
-  fn parse(parse_stream: ParseStream) -> syn::Result<Self> {
-    let ident = parse_stream.parse::<Ident>()?;
-
-    if parse_stream.parse::<Token![=]>().is_ok() {
-      let value = parse_stream.parse::<LitStr>()?;
-
-      Ok(Self {
-        ident,
-        value: Some(value),
-      })
-    } else {
-      Ok(Self { ident, value: None })
+impl Arg {
+  fn parse_meta(meta: ParseNestedMeta) -> syn::Result<Self> {
+    let mut arg = Self {
+      key: meta.path.to_token_stream().to_string(),
+      value: None,
+      nested: vec![],
+      span: meta.path.span(),
+    };
+
+    if meta.input.peek(Token![=]) {
+      arg.value = Some(match meta.value()?.parse::<Expr>()? {
+        Expr::Lit(ExprLit {
+          lit: Lit::Str(lit), ..
+        }) => lit.value(),
+        expr => expr.to_token_stream().to_string(),
+      });
+    } else if meta.input.peek(Paren) {
+      let group = meta.input.parse::<Group>()?;
+
+      // lists that aren't made of args are kept empty
+      let parser = syn::meta::parser(|meta| {
+        arg.nested.push(Self::parse_meta(meta)?);
+        Ok(())
+      });
+      if parser.parse2(group.stream()).is_err() {
+        arg.nested.clear();
+      }
     }
+
+    Ok(arg)
   }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
-struct AttributeArgs {
-  args: Punctuated<Arg, Token![,]>,
+fn parse_args(attr: &Attribute) -> syn::Result<Vec<Arg>> {
+  let mut args = vec![];
+  attr.parse_nested_meta(|meta| {
+    args.push(Arg::parse_meta(meta)?);
+    Ok(())
+  })?;
+  Ok(args)
 }
 
-impl Parse for AttributeArgs {
-  fn parse(parse_stream: ParseStream) -> syn::Result<Self> {
-    Ok(Self {
-      args: parse_stream.parse_terminated(Arg::parse, Token![,])?,
-    })
+fn parse_rename_all(value: Option<String>) -> TEResult<AttributeInfoItem<RenameAll>> {
+  if let Some(ref value) = value {
+    let rename_all =
+      RenameAll::from_str(value).map_err(|_| TEError::UnknownValueOfRenameAll(value.clone()))?;
+
+    Ok(AttributeInfoItem::Set(rename_all))
+  } else {
+    Ok(AttributeInfoItem::SetEmpty)
   }
 }
 
-pub fn parse_attributes(attrs: &[Attribute]) -> TEResult<AttributeInfo> {
+pub(crate) fn warn_at(ctx: &ParseContext, span: Span, message: impl Display) {
+  warn!("{}: {message}", ctx.location(span));
+}
+
+/// serde attributes that don't affect the json
+const SERDE_IGNORED: &[&str] = &[
+  "deny_unknown_fields",
+  "bound",
+  "crate",
+  "borrow",
+  "expecting",
+  "remote",
+  "getter",
+  "variant_identifier",
+  "field_identifier",
+];
+
+/// attributes that aren't `te`, `serde`, `serde_as` or `derive` are ignored,
+/// unknown or unsupported args inside them are ignored with a warning
+pub fn parse_attributes(ctx: &ParseContext, attrs: &[Attribute]) -> TEResult<AttributeInfo> {
   let mut result = AttributeInfo::default();
 
-  for attr in attrs {
-    if let Meta::List(meta_list) = &attr.meta {
-      macro_rules! match_apply {
-        ($key:expr, $value:expr, $result:expr; $($match_str:literal => $field:ident,)*) => {
-          match $key {
-            $($match_str => $result.$field = $value,)*
-            _ => {}
-          }
-        };
-      }
+  for attr in &expand_cfg_attr(ctx, attrs) {
+    if attr.path().is_ident("non_exhaustive") {
+      result.non_exhaustive = AttributeInfoItem::SetEmpty;
+      continue;
+    }
+    if !matches!(attr.meta, Meta::List(_)) {
+      continue;
+    }
+
+    macro_rules! match_apply {
+      ($key:expr, $value:expr, $result:expr; $($match_str:literal => $field:ident,)*) => {
+        match $key {
+          $($match_str => {
+            $result.$field = $value;
+            true
+          })*
+          _ => false,
+        }
+      };
+    }
+
+    let name = attr.path().segments.last().unwrap().ident.to_string();
+    if !matches!(name.as_str(), "te" | "serde" | "serde_as" | "derive") {
+      continue;
+    }
 
-      match meta_list
-        .path
-        .segments
-        .last()
-        .unwrap()
-        .ident
-        .to_string()
-        .as_str()
-      {
-        "te" => {
-          let args = syn::parse2::<AttributeArgs>(meta_list.tokens.clone())?;
-          for arg in args.args {
-            let key = arg.ident.to_string();
-            let value = arg.value.map(|it| it.value());
-
-            match_apply! { key.as_str(), value.into(), result;
-              "entry" => entry,
-
-              "retype" => retype,
-              "rename" => rename,
+    let args = match parse_args(attr) {
+      Ok(args) => args,
+      Err(err) => {
+        warn_at(
+          ctx,
+          err.span(),
+          format!("unable to parse {name}, ignored: {err}"),
+        );
+        continue;
+      }
+    };
+
+    for arg in args {
+      let key = arg.key.as_str();
+      let value = arg.value.clone();
+
+      let supported = match name.as_str() {
+        "te" => match key {
+          "big_int" => {
+            if let Some(ref value) = value {
+              let big_int = BigIntStyle::from_str(value)
+                .map_err(|_| TEError::UnknownValueOfBigInt(value.clone()))?;
+
+              result.big_int = AttributeInfoItem::Set(big_int);
+            } else {
+              result.big_int = AttributeInfoItem::SetEmpty;
             }
+            true
           }
-        }
-        "serde" => {
-          let args = syn::parse2::<AttributeArgs>(meta_list.tokens.clone())?;
-          for arg in args.args {
-            let key = arg.ident.to_string();
-            let value = arg.value.map(|it| it.value());
-
-            if key.eq("rename_all") {
-              if let Some(ref value) = value {
-                let rename_all = RenameAll::from_str(value)
-                  .map_err(|_| TEError::UnknownValueOfRenameAll(value.clone()))?;
-
-                result.rename_all = AttributeInfoItem::Set(rename_all);
-              } else {
-                result.rename_all = AttributeInfoItem::SetEmpty;
+          // for a hand written serde_repr like implementation
+          "repr" => {
+            result.serialize_repr = AttributeInfoItem::SetEmpty;
+            result.deserialize_repr = AttributeInfoItem::SetEmpty;
+            true
+          }
+          _ => match_apply! { key, value.into(), result;
+            "entry" => entry,
+
+            "retype" => retype,
+            "rename" => rename,
+          },
+        },
+        "serde" => match key {
+          // rename(serialize = "...", deserialize = "...")
+          "rename" | "rename_all" | "rename_all_fields" if !arg.nested.is_empty() => {
+            for nested in &arg.nested {
+              let value = nested.value.clone();
+
+              let supported = match (key, nested.key.as_str()) {
+                ("rename", "serialize") => {
+                  result.rename_serialize = value.into();
+                  true
+                }
+                ("rename", "deserialize") => {
+                  result.rename_deserialize = value.into();
+                  true
+                }
+                ("rename_all", "serialize") => {
+                  result.rename_all_serialize = parse_rename_all(value)?;
+                  true
+                }
+                ("rename_all", "deserialize") => {
+                  result.rename_all_deserialize = parse_rename_all(value)?;
+                  true
+                }
+                ("rename_all_fields", "serialize") => {
+                  result.rename_all_fields_serialize = parse_rename_all(value)?;
+                  true
+                }
+                ("rename_all_fields", "deserialize") => {
+                  result.rename_all_fields_deserialize = parse_rename_all(value)?;
+                  true
+                }
+                _ => false,
+              };
+
+              if !supported {
+                let message = format!(
+                  "unsupported attribute {name}({key}({})), ignored",
+                  nested.key
+                );
+                warn_at(ctx, nested.span, message);
               }
             }
-
-            match_apply! { key.as_str(), value.into(), result;
-              "rename" => rename,
-              "tag" => tag,
-              "content" => tag_content,
-              "skip" => skip,
-              "skip_serializing" => skip_serializing,
-            }
+            true
+          }
+          "rename_all" => {
+            result.rename_all = parse_rename_all(value)?;
+            true
+          }
+          "rename_all_fields" => {
+            result.rename_all_fields = parse_rename_all(value)?;
+            true
+          }
+          "alias" => {
+            result.alias.extend(value);
+            true
+          }
+          key if SERDE_IGNORED.contains(&key) => true,
+          _ => match_apply! { key, value.into(), result;
+            "rename" => rename,
+            "tag" => tag,
+            "content" => tag_content,
+            "untagged" => untagged,
+            "other" => other,
+            "transparent" => transparent,
+            "from" => from,
+            "try_from" => try_from,
+            "into" => into,
+            "with" => with,
+            "serialize_with" => serialize_with,
+            "deserialize_with" => deserialize_with,
+            "skip" => skip,
+            "flatten" => flatten,
+            "skip_serializing" => skip_serializing,
+            "skip_serializing_if" => skip_serializing_if,
+            "skip_deserializing" => skip_deserializing,
+            "default" => default,
+          },
+        },
+        "serde_as" => match_apply! { key, value.into(), result;
+          "as" => serde_as,
+        },
+        "derive" => {
+          match key.rsplit("::").next().unwrap().trim() {
+            "Serialize_repr" => result.serialize_repr = AttributeInfoItem::SetEmpty,
+            "Deserialize_repr" => result.deserialize_repr = AttributeInfoItem::SetEmpty,
+            _ => {}
           }
+          // other derives don't matter
+          true
         }
-        _ => {}
+        _ => unreachable!(),
+      };
+
+      if !supported {
+        warn_at(
+          ctx,
+          arg.span,
+          format!("unsupported attribute {name}({key}), ignored"),
+        );
       }
     }
   }
 
   Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::RenameAll::*;
+  use super::*;
+
+  const RULES: [RenameAll; 8] = [
+    LowerCase,
+    UpperCase,
+    PascalCase,
+    CamelCase,
+    SnakeCase,
+    ScreamingSnakeCase,
+    KebabCase,
+    ScreamingKebabCase,
+  ];
+
+  /// the cases of serde_derive, plus `HTTPError`, in the order of [RULES]
+  #[test]
+  fn rename_variants() {
+    for (original, expected) in [
+      (
+        "Outcome",
+        [
+          "outcome", "OUTCOME", "Outcome", "outcome", "outcome", "OUTCOME", "outcome", "OUTCOME",
+        ],
+      ),
+      (
+        "VeryTasty",
+        [
+          "verytasty",
+          "VERYTASTY",
+          "VeryTasty",
+          "veryTasty",
+          "very_tasty",
+          "VERY_TASTY",
+          "very-tasty",
+          "VERY-TASTY",
+        ],
+      ),
+      ("A", ["a", "A", "A", "a", "a", "A", "a", "A"]),
+      (
+        "Z42",
+        ["z42", "Z42", "Z42", "z42", "z42", "Z42", "z42", "Z42"],
+      ),
+      (
+        "HTTPError",
+        [
+          "httperror",
+          "HTTPERROR",
+          "HTTPError",
+          "hTTPError",
+          "h_t_t_p_error",
+          "H_T_T_P_ERROR",
+          "h-t-t-p-error",
+          "H-T-T-P-ERROR",
+        ],
+      ),
+    ] {
+      for (rule, expected) in RULES.iter().zip(expected) {
+        assert_eq!(
+          rule.apply_to_variant(original),
+          expected,
+          "{original} {rule:?}"
+        );
+      }
+    }
+  }
+
+  /// the cases of serde_derive, plus `field_1a`, in the order of [RULES]
+  #[test]
+  fn rename_fields() {
+    for (original, expected) in [
+      (
+        "outcome",
+        [
+          "outcome", "OUTCOME", "Outcome", "outcome", "outcome", "OUTCOME", "outcome", "OUTCOME",
+        ],
+      ),
+      (
+        "very_tasty",
+        [
+          "very_tasty",
+          "VERY_TASTY",
+          "VeryTasty",
+          "veryTasty",
+          "very_tasty",
+          "VERY_TASTY",
+          "very-tasty",
+          "VERY-TASTY",
+        ],
+      ),
+      ("a", ["a", "A", "A", "a", "a", "A", "a", "A"]),
+      (
+        "z42",
+        ["z42", "Z42", "Z42", "z42", "z42", "Z42", "z42", "Z42"],
+      ),
+      (
+        "field_1a",
+        [
+          "field_1a", "FIELD_1A", "Field1a", "field1a", "field_1a", "FIELD_1A", "field-1a",
+          "FIELD-1A",
+        ],
+      ),
+    ] {
+      for (rule, expected) in RULES.iter().zip(expected) {
+        assert_eq!(
+          rule.apply_to_field(original),
+          expected,
+          "{original} {rule:?}"
+        );
+      }
+    }
+  }
+}
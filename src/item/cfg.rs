@@ -0,0 +1,173 @@
+/*
+ * Copyright 2021-2023 Golden_Water
+ * SPDX-License-Identifier: AGPL-3.0-only
+ */
+
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Attribute, Expr, ExprLit, Lit, Meta, Token};
+
+use crate::item::attribute_info::warn_at;
+use crate::item::parse_context::ParseContext;
+
+/// whether the `#[cfg(...)]` of an item, variant or field hold,
+/// predicates that can't be parsed are treated as true
+pub fn is_cfg_enabled(ctx: &ParseContext, attrs: &[Attribute]) -> bool {
+  expand_cfg_attr(ctx, attrs)
+    .iter()
+    .filter(|it| it.path().is_ident("cfg"))
+    .all(|attr| {
+      match attr
+        .meta
+        .require_list()
+        .and_then(|it| it.parse_args::<Meta>())
+        .and_then(|it| eval_predicate(ctx, &it))
+      {
+        Ok(enabled) => enabled,
+        Err(err) => {
+          warn_at(ctx, err.span(), format!("unable to evaluate cfg: {err}"));
+          true
+        }
+      }
+    })
+}
+
+/// replace every `#[cfg_attr(predicate, attrs...)]` with the attributes if the predicate holds,
+/// predicates that can't be parsed are treated as false
+pub fn expand_cfg_attr(ctx: &ParseContext, attrs: &[Attribute]) -> Vec<Attribute> {
+  let mut result = vec![];
+
+  for attr in attrs {
+    if !attr.path().is_ident("cfg_attr") {
+      result.push(attr.clone());
+      continue;
+    }
+
+    let parsed = attr.meta.require_list().and_then(|it| {
+      it.parse_args_with(|input: syn::parse::ParseStream| {
+        let predicate = input.parse::<Meta>()?;
+        input.parse::<Token![,]>()?;
+        let metas = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
+        Ok((predicate, metas))
+      })
+    });
+    let (predicate, metas) = match parsed {
+      Ok(parsed) => parsed,
+      Err(err) => {
+        warn_at(ctx, err.span(), format!("unable to parse cfg_attr: {err}"));
+        continue;
+      }
+    };
+
+    match eval_predicate(ctx, &predicate) {
+      Ok(true) => {
+        // the attributes may be cfg_attr again
+        let expanded = metas
+          .into_iter()
+          .map(|meta| Attribute {
+            meta,
+            ..attr.clone()
+          })
+          .collect::<Vec<_>>();
+        result.extend(expand_cfg_attr(ctx, &expanded));
+      }
+      Ok(false) => {}
+      Err(err) => warn_at(
+        ctx,
+        err.span(),
+        format!("unable to evaluate cfg_attr: {err}"),
+      ),
+    }
+  }
+
+  result
+}
+
+/// `all(...)`, `any(...)`, `not(...)`, `name` and `name = "value"`,
+/// checked against the features and cfg options in the config
+fn eval_predicate(ctx: &ParseContext, predicate: &Meta) -> syn::Result<bool> {
+  let name = predicate
+    .path()
+    .get_ident()
+    .ok_or_else(|| syn::Error::new(predicate.path().span(), "expected an identifier"))?
+    .to_string();
+
+  match predicate {
+    Meta::Path(_) => Ok(ctx.config.is_cfg_set(&name, None)),
+    Meta::NameValue(name_value) => match &name_value.value {
+      Expr::Lit(ExprLit {
+        lit: Lit::Str(value),
+        ..
+      }) => Ok(ctx.config.is_cfg_set(&name, Some(&value.value()))),
+      value => Err(syn::Error::new(value.span(), "expected a string literal")),
+    },
+    Meta::List(list) => {
+      let predicates = list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+      let mut results = predicates.iter().map(|it| eval_predicate(ctx, it));
+
+      match name.as_str() {
+        "all" => results.try_fold(true, |acc, it| Ok(acc & it?)),
+        "any" => results.try_fold(false, |acc, it| Ok(acc | it?)),
+        "not" if predicates.len() == 1 => Ok(!results.next().unwrap()?),
+        "not" => Err(syn::Error::new(
+          list.span(),
+          "not expects exactly one predicate",
+        )),
+        _ => Err(syn::Error::new(
+          list.path.span(),
+          format!("unknown predicate {name}"),
+        )),
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config::Config;
+  use crate::use_path::{PathFs, UsePath};
+
+  #[test]
+  fn eval_predicates() {
+    let config = Config {
+      features: vec!["serde".to_string()],
+      cfg: vec!["unix".to_string(), r#"target_os = "linux""#.to_string()],
+      ..Default::default()
+    };
+    let path = UsePath::<PathFs>::from("lib.rs").to_rs();
+    let (uses, local_items) = (vec![], vec![]);
+    let ctx = ParseContext::new(&config, &path, &uses, &local_items);
+
+    for (predicate, expected) in [
+      (r#"feature = "serde""#, true),
+      (r#"feature = "extra""#, false),
+      ("unix", true),
+      ("windows", false),
+      (r#"target_os = "linux""#, true),
+      (r#"target_os = "macos""#, false),
+      ("all()", true),
+      ("any()", false),
+      (r#"all(unix, feature = "serde")"#, true),
+      (r#"all(unix, feature = "extra")"#, false),
+      (r#"any(windows, feature = "serde")"#, true),
+      ("not(windows)", true),
+      (
+        r#"not(any(windows, all(unix, target_os = "linux")))"#,
+        false,
+      ),
+    ] {
+      let meta = syn::parse_str::<Meta>(predicate).unwrap();
+      assert_eq!(
+        eval_predicate(&ctx, &meta).unwrap(),
+        expected,
+        "{predicate}"
+      );
+    }
+
+    for predicate in ["not(unix, windows)", "unknown(unix)", r#"feature = 1"#] {
+      let meta = syn::parse_str::<Meta>(predicate).unwrap();
+      assert!(eval_predicate(&ctx, &meta).is_err(), "{predicate}");
+    }
+  }
+}
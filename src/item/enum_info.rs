@@ -5,64 +5,227 @@
 
 use std::collections::HashSet;
 
-use log::error;
 use swc_core::ecma::ast;
-use syn::{ItemEnum, Variant};
+use syn::ext::IdentExt;
+use syn::{Expr, ExprGroup, ExprLit, ExprParen, ExprUnary, ItemEnum, Lit, UnOp, Variant};
 
-use crate::item::attribute_info::{parse_attributes, AttributeInfo, RenameAll};
+use crate::config::NumericEnumStyle;
+
+use crate::item::attribute_info::{parse_attributes, AttributeInfo, Direction, RenameAll};
+use crate::item::cfg::is_cfg_enabled;
 use crate::item::field_info::FieldInfo;
+use crate::item::parse_context::{parse_generics, ParseContext};
+use crate::item::proxy_info::ProxyInfo;
 use crate::item::struct_info::StructInfo;
 use crate::item::type_info::TypeInfo;
-use crate::use_path::{PathRs, UsePath};
-use crate::utils::rename_name;
 use crate::utils::ts_ast_utils::{
-  create_expr_ident, create_expr_str, create_ident, create_module_decl_item,
-  create_property_type_element, create_str_lit_type, create_type_alias_decl, create_type_array,
-  create_type_lit, create_type_ref, type_to_type_ann,
+  create_enum_decl, create_expr_key, create_expr_str, create_ident, create_keyword_type,
+  create_module_decl_item, create_number_lit_type, create_open_type,
+  create_optional_property_type_element, create_property_type_element, create_str_lit_type,
+  create_type_alias_decl, create_type_array, create_type_intersection, create_type_lit,
+  create_type_param_decl, create_type_ref, type_to_type_ann,
 };
-use crate::{TEResult, TsAst};
+use crate::utils::{rename_name, rename_variant};
+use crate::{TEError, TEResult, TsAst};
 
 #[derive(Debug, Clone)]
 pub struct EnumInfo {
   pub name: String,
+  pub generics: Vec<String>,
   pub attr: AttributeInfo,
+  pub proxy: ProxyInfo,
   pub variants: Vec<VariantInfo>,
+  /// see [Config::numeric_enum](crate::config::Config::numeric_enum)
+  pub numeric_style: NumericEnumStyle,
+  /// see [Config::enum_fallback](crate::config::Config::enum_fallback)
+  pub fallback: Option<String>,
 }
 
 impl EnumInfo {
-  pub fn parse_item_enum(
-    path: &UsePath<PathRs>,
-    uses: &Vec<UsePath<PathRs>>,
-    local_items: &Vec<String>,
-    item_enum: &ItemEnum,
-  ) -> TEResult<EnumInfo> {
-    let variants = item_enum
+  pub fn parse_item_enum(ctx: &ParseContext, item_enum: &ItemEnum) -> TEResult<EnumInfo> {
+    let generics = parse_generics(&item_enum.generics);
+    let ctx = ctx.clone().with_generics(generics.clone());
+
+    let name = item_enum.ident.to_string();
+    let item_variants = item_enum
       .variants
       .iter()
-      .map(|it| VariantInfo::parse_variant(path, uses, local_items, it))
+      .filter(|it| is_cfg_enabled(&ctx, &it.attrs))
+      .collect::<Vec<_>>();
+    let mut variants = item_variants
+      .iter()
+      .map(|it| VariantInfo::parse_variant(&ctx, it))
       .collect::<TEResult<Vec<_>>>()?;
+    let attr = parse_attributes(&ctx, &item_enum.attrs)?;
+
+    // without an explicit one, the discriminant is the previous one plus one
+    let mut next = Some(0);
+    for (variant, item_variant) in variants.iter_mut().zip(&item_variants) {
+      variant.discriminant = match &item_variant.discriminant {
+        Some((_, expr)) => parse_discriminant(expr),
+        None => next,
+      };
+      next = variant.discriminant.and_then(|it| it.checked_add(1));
+    }
+
+    if attr.tag.is_set() && !attr.tag_content.is_set() && !attr.untagged.is_set() {
+      for (variant, item_variant) in variants.iter().zip(&item_variants) {
+        // skipped variants are never tagged
+        let directions = [Direction::Serialize, Direction::Deserialize]
+          .into_iter()
+          .filter(|it| !variant.attr.is_skipped(*it))
+          .collect::<Vec<_>>();
+
+        let reason = if directions.is_empty() {
+          continue;
+        } else if variant.is_tuple_variant() && variant.fields.len() > 1 {
+          "tuple variants aren't supported by serde"
+        } else if variant.is_tuple_variant()
+          && directions
+            .iter()
+            .any(|it| !variant.fields[0].ty_for(*it).is_object_like())
+        {
+          "the content isn't a struct or map"
+        } else {
+          continue;
+        };
+
+        return Err(TEError::InvalidInternallyTaggedVariant(
+          format!(
+            "{name}::{} at {}",
+            variant.name,
+            ctx.location(item_variant.ident.span())
+          ),
+          reason.to_string(),
+        ));
+      }
+    }
+
+    if attr.is_repr(Direction::Serialize) || attr.is_repr(Direction::Deserialize) {
+      for variant in &variants {
+        let err = |reason: String| Err(TEError::InvalidNumericEnum(name.clone(), reason));
+
+        if !variant.fields.is_empty() {
+          return err(format!("{} isn't a unit variant", variant.name));
+        }
+        if variant.discriminant.is_none() {
+          return err(format!(
+            "unable to evaluate the discriminant of {}",
+            variant.name
+          ));
+        }
+      }
+    }
 
     Ok(EnumInfo {
-      name: item_enum.ident.to_string(),
-      attr: parse_attributes(&item_enum.attrs)?,
+      name,
+      generics,
+      proxy: ProxyInfo::parse(&ctx, &attr)?,
+      attr,
       variants,
+      numeric_style: ctx.config.numeric_enum,
+      fallback: ctx.config.enum_fallback.clone(),
     })
   }
 
-  pub fn to_ts_ast(&self) -> TsAst<Vec<ast::ModuleItem>> {
-    let name = rename_name(&self.attr, None, Some(&self.name)).unwrap();
+  /// whether there may be values that aren't one of the variants,
+  /// `#[non_exhaustive]` enums may get new variants,
+  /// and a `#[serde(other)]` variant accepts any unknown tag when deserializing
+  pub fn is_open(&self, direction: Direction) -> bool {
+    self.attr.non_exhaustive.is_set()
+      || (direction != Direction::Serialize
+        && self.variants.iter().any(|it| it.attr.other.is_set()))
+  }
+
+  /// the member for unknown values of an open enum, `None` for untagged enums
+  fn fallback_type(&self) -> Option<ast::TsType> {
+    if let Some(fallback) = &self.fallback {
+      return Some(create_type_ref(fallback, None));
+    }
+
+    let open_string = || create_open_type(ast::TsKeywordTypeKind::TsStringKeyword);
+
+    if self.attr.untagged.is_set() {
+      None
+    } else if let Some(tag) = self.attr.tag.get() {
+      let mut members = vec![create_property_type_element(
+        create_expr_key(tag),
+        type_to_type_ann(open_string()),
+      )];
+      if let Some(content) = self.attr.tag_content.get() {
+        members.push(create_optional_property_type_element(
+          create_expr_key(content),
+          type_to_type_ann(create_keyword_type(
+            ast::TsKeywordTypeKind::TsUnknownKeyword,
+          )),
+        ));
+      }
+      Some(create_type_lit(members))
+    } else {
+      Some(open_string())
+    }
+  }
+
+  /// enums serialized as the discriminant by serde_repr
+  fn to_ts_ast_numeric(&self, name: &str, direction: Direction) -> TsAst<Vec<ast::ModuleItem>> {
+    let id = create_ident(direction.type_name(name));
+    let members = self
+      .variants
+      .iter()
+      .map(|it| (it.name.clone(), it.discriminant.unwrap()));
+
+    let decl = match self.numeric_style {
+      NumericEnumStyle::Union => {
+        let mut types = members
+          .map(|(_, value)| Box::new(create_number_lit_type(value)))
+          .collect::<Vec<_>>();
+        if self.attr.non_exhaustive.is_set() {
+          types.push(Box::new(create_open_type(
+            ast::TsKeywordTypeKind::TsNumberKeyword,
+          )));
+        }
+
+        create_type_alias_decl(id, None, create_type_array(types))
+      }
+      NumericEnumStyle::Enum => create_enum_decl(id, false, members.collect()),
+      NumericEnumStyle::ConstEnum => create_enum_decl(id, true, members.collect()),
+    };
+
+    TsAst {
+      ast: vec![create_module_decl_item(decl)],
+      dependencies: Default::default(),
+    }
+  }
+
+  pub fn to_ts_ast(&self, direction: Direction) -> TsAst<Vec<ast::ModuleItem>> {
+    let name = rename_name(&self.attr, None, Some(&self.name), direction).unwrap();
+
+    if let Some(ty) = self.proxy.get(direction) {
+      let ts_ast = ty.to_ts_ast(direction);
+
+      return TsAst {
+        ast: vec![create_module_decl_item(create_type_alias_decl(
+          create_ident(direction.type_name(&name)),
+          create_type_param_decl(&self.generics),
+          *ts_ast.ast.type_ann,
+        ))],
+        dependencies: ts_ast.dependencies,
+      };
+    }
+
+    if self.attr.is_repr(direction) {
+      return self.to_ts_ast_numeric(&name, direction);
+    }
 
     let mut dependencies = HashSet::new();
     let mut variants = vec![];
     let mut variant_types = vec![];
 
     for variant_info in &self.variants {
-      let ts_ast = variant_info.to_ts_ast(
-        &name,
-        self.attr.rename_all.as_ref().into(),
-        self.attr.tag.as_ref().into(),
-        self.attr.tag_content.as_ref().into(),
-      );
+      if variant_info.attr.is_skipped(direction) {
+        continue;
+      }
+      let ts_ast = variant_info.to_ts_ast(self, &name, direction);
 
       dependencies.extend(ts_ast.dependencies);
       variants.push(Box::new(ts_ast.ast.0));
@@ -71,8 +234,13 @@ impl EnumInfo {
       }
     }
 
+    if self.is_open(direction) {
+      variants.extend(self.fallback_type().map(Box::new));
+    }
+
     let module_item = create_module_decl_item(create_type_alias_decl(
-      create_ident(&name),
+      create_ident(direction.type_name(&name)),
+      create_type_param_decl(&self.generics),
       create_type_array(variants),
     ));
 
@@ -90,19 +258,26 @@ pub struct VariantInfo {
   pub name: String,
   pub attr: AttributeInfo,
   pub fields: Vec<FieldInfo>,
+  /// `None` if it can't be evaluated
+  pub discriminant: Option<i128>,
 }
 
 impl VariantInfo {
-  pub fn parse_variant(
-    path: &UsePath<PathRs>,
-    uses: &Vec<UsePath<PathRs>>,
-    types: &Vec<String>,
-    variant: &Variant,
-  ) -> TEResult<Self> {
+  pub fn parse_variant(ctx: &ParseContext, variant: &Variant) -> TEResult<Self> {
+    let attr = parse_attributes(ctx, &variant.attrs)?;
+    // the fields of a variant that is never exported don't have to be known
+    let fields = if attr.is_skipped(Direction::Serialize) && attr.is_skipped(Direction::Deserialize)
+    {
+      vec![]
+    } else {
+      FieldInfo::parse_fields(ctx, &variant.fields)?
+    };
+
     Ok(Self {
-      name: variant.ident.to_string(),
-      attr: parse_attributes(&variant.attrs)?,
-      fields: FieldInfo::parse_fields(path, uses, types, &variant.fields)?,
+      name: variant.ident.unraw().to_string(),
+      attr,
+      fields,
+      discriminant: None,
     })
   }
 
@@ -128,81 +303,157 @@ impl VariantInfo {
     true
   }
 
+  /// the name of the variant, renamed by the `rename_all` of the enum
+  pub fn rename(&self, enum_info: &EnumInfo, direction: Direction) -> String {
+    rename_variant(
+      &self.attr,
+      enum_info.attr.rename_all(direction),
+      &self.name,
+      direction,
+    )
+  }
+
+  /// the rule for the fields of the variant, the `rename_all` of the variant takes precedence
+  /// over the `rename_all_fields` of the enum
+  pub fn rename_all_fields<'a>(
+    &'a self,
+    enum_info: &'a EnumInfo,
+    direction: Direction,
+  ) -> Option<&'a RenameAll> {
+    self
+      .attr
+      .rename_all(direction)
+      .or(enum_info.attr.rename_all_fields(direction))
+  }
+
   pub fn is_unit_variant(&self) -> bool {
     if self.fields.is_empty() {
       return true;
     }
-    if self.fields.len() == 1 && self.fields[0].attr.is_skipped() {
+    if self.fields.len() == 1 && self.fields[0].attr.is_skipped(Direction::Both) {
       return true;
     }
     false
   }
 
-  pub fn to_ts_ast<Name: AsRef<str>>(
+  /// `enum_name` is the renamed name of `enum_info`
+  pub fn to_ts_ast(
     &self,
-    enum_name: Name,
-    rename_all: Option<&RenameAll>,
-    tag: Option<&String>,
-    content: Option<&String>,
+    enum_info: &EnumInfo,
+    enum_name: &str,
+    direction: Direction,
   ) -> TsAst<(ast::TsType, Option<ast::ModuleItem>)> {
-    let name = rename_name(&self.attr, rename_all, Some(&self.name)).unwrap();
-    let variant_type_name = format!("{}_{}", enum_name.as_ref(), name);
+    let generics = &enum_info.generics;
+    let tag = enum_info.attr.tag.get();
+    let content = enum_info.attr.tag_content.get();
+
+    let name = self.rename(enum_info, direction);
+    let variant_type_name = format!("{enum_name}_{name}");
+    let variant_type_ref = || {
+      let params = generics
+        .iter()
+        .map(|it| Box::new(create_type_ref(it, None)))
+        .collect::<Vec<_>>();
+      create_type_ref(
+        direction.type_name(&variant_type_name),
+        (!params.is_empty()).then_some(params),
+      )
+    };
 
     let mut dependencies = HashSet::new();
 
     fn to_struct_ast(
       this: &VariantInfo,
+      enum_info: &EnumInfo,
       name: &str,
       mut fields_prepend: Vec<FieldInfo>,
+      direction: Direction,
     ) -> TsAst<ast::ModuleItem> {
       fields_prepend.extend(this.fields.clone());
+      // the attributes of the variant are about the variant itself,
+      // only the rule for the fields applies to the struct
       StructInfo {
         name: name.to_string(),
+        generics: enum_info.generics.clone(),
         fields: fields_prepend,
+        proxy: Default::default(),
         attr: AttributeInfo {
-          rename: None.into(),
-          ..this.attr.clone()
+          rename_all: this.rename_all_fields(enum_info, direction).cloned().into(),
+          ..Default::default()
         },
       }
-      .to_ts_ast()
+      .to_ts_ast(direction)
     }
 
-    let ast = if let Some(tag) = tag.cloned() {
+    let ast = if enum_info.attr.untagged.is_set() {
+      // region untagged
+      if self.is_unit_variant() {
+        (
+          create_keyword_type(ast::TsKeywordTypeKind::TsNullKeyword),
+          None,
+        )
+      } else {
+        let ts_ast = to_struct_ast(self, enum_info, &variant_type_name, vec![], direction);
+        dependencies = ts_ast.dependencies;
+
+        (variant_type_ref(), Some(ts_ast.ast))
+      }
+      // endregion
+    } else if let Some(tag) = tag.cloned() {
       if self.is_unit_variant() {
         (
           create_type_lit(vec![create_property_type_element(
-            create_expr_ident(tag),
+            create_expr_key(tag),
             type_to_type_ann(create_str_lit_type(name)),
           )]),
           None,
         )
       } else if let Some(content) = content.cloned() {
         // region adjacently tagged
-        let ts_ast = to_struct_ast(self, &variant_type_name, vec![]);
+        let ts_ast = to_struct_ast(self, enum_info, &variant_type_name, vec![], direction);
         dependencies = ts_ast.dependencies;
 
         let type_in_enum_define = create_type_lit(vec![
           create_property_type_element(
-            create_expr_ident(tag),
+            create_expr_key(tag),
             type_to_type_ann(create_str_lit_type(name)),
           ),
           create_property_type_element(
-            create_expr_ident(content),
-            type_to_type_ann(create_type_ref(variant_type_name, None)),
+            create_expr_key(content),
+            type_to_type_ann(variant_type_ref()),
           ),
         ]);
         (type_in_enum_define, Some(ts_ast.ast))
         // endregion
       } else {
         // region internally tagged
+        let tag_type = || {
+          create_type_lit(vec![create_property_type_element(
+            create_expr_key(&tag),
+            type_to_type_ann(create_str_lit_type(&name)),
+          )])
+        };
+
+        // newtype variants, the tag is added into the content
         if self.is_tuple_variant() {
-          error!(
-            "internally tagged with tuple variant isn't expected, this will produce a wrong result"
-          );
+          let ty = self.fields[0].ty_for(direction);
+          let ts_ast = ty.to_ts_ast(direction);
+
+          return TsAst {
+            ast: match ty.see_through() {
+              TypeInfo::Unit | TypeInfo::PhantomData => (tag_type(), None),
+              _ => (
+                create_type_intersection(vec![Box::new(tag_type()), ts_ast.ast.type_ann]),
+                None,
+              ),
+            },
+            dependencies: ts_ast.dependencies,
+          };
         }
 
         let ts_ast = to_struct_ast(
           self,
+          enum_info,
           &variant_type_name,
           vec![FieldInfo {
             name: self.fields[0].name.clone(),
@@ -211,11 +462,13 @@ impl VariantInfo {
               rename: Some(tag).into(),
               ..Default::default()
             },
+            with: Default::default(),
           }],
+          direction,
         );
         dependencies = ts_ast.dependencies;
 
-        (create_type_ref(variant_type_name, None), Some(ts_ast.ast))
+        (variant_type_ref(), Some(ts_ast.ast))
         // endregion
       }
     } else {
@@ -223,12 +476,12 @@ impl VariantInfo {
       if self.is_unit_variant() {
         (create_str_lit_type(name), None)
       } else {
-        let ts_ast = to_struct_ast(self, &variant_type_name, vec![]);
+        let ts_ast = to_struct_ast(self, enum_info, &variant_type_name, vec![], direction);
         dependencies = ts_ast.dependencies;
 
         let type_in_enum_define = create_type_lit(vec![create_property_type_element(
           create_expr_str(name),
-          type_to_type_ann(create_type_ref(variant_type_name, None)),
+          type_to_type_ann(variant_type_ref()),
         )]);
         (type_in_enum_define, Some(ts_ast.ast))
       }
@@ -238,3 +491,21 @@ impl VariantInfo {
     TsAst { ast, dependencies }
   }
 }
+
+/// integer literals, optionally negated
+fn parse_discriminant(expr: &Expr) -> Option<i128> {
+  match expr {
+    Expr::Lit(ExprLit {
+      lit: Lit::Int(lit), ..
+    }) => lit.base10_parse().ok(),
+    Expr::Unary(ExprUnary {
+      op: UnOp::Neg(_),
+      expr,
+      ..
+    }) => parse_discriminant(expr).map(|it| -it),
+    Expr::Paren(ExprParen { expr, .. }) | Expr::Group(ExprGroup { expr, .. }) => {
+      parse_discriminant(expr)
+    }
+    _ => None,
+  }
+}
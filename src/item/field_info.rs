@@ -3,15 +3,23 @@
  * SPDX-License-Identifier: AGPL-3.0-only
  */
 
+use std::iter;
+
 use swc_core::ecma::ast;
-use syn::__private::ToTokens;
-use syn::{Fields, Type};
+use syn::ext::IdentExt;
+use syn::Fields;
 
-use crate::item::attribute_info::{parse_attributes, AttributeInfo, RenameAll};
+use crate::item::attribute_info::{parse_attributes, AttributeInfo, Direction, RenameAll};
+use crate::item::cfg::is_cfg_enabled;
+use crate::item::parse_context::ParseContext;
 use crate::item::type_info::TypeInfo;
-use crate::use_path::{PathRs, UsePath};
+use crate::item::with_info::WithInfo;
 use crate::utils::rename_name;
-use crate::utils::ts_ast_utils::{create_expr_ident, create_ident, create_property_type_element};
+use crate::utils::ts_ast_utils::{
+  create_expr_key, create_ident, create_index_signature_type_lit,
+  create_optional_property_type_element, create_property_type_element, create_type_array,
+  create_type_lit, create_type_ref,
+};
 use crate::{TEError, TEResult, TsAst};
 
 #[derive(Debug, Clone)]
@@ -19,59 +27,154 @@ pub struct FieldInfo {
   pub name: Option<String>,
   pub ty: TypeInfo,
   pub attr: AttributeInfo,
+  pub with: WithInfo,
 }
 
 impl FieldInfo {
-  pub fn parse_fields(
-    path: &UsePath<PathRs>,
-    uses: &Vec<UsePath<PathRs>>,
-    local_items: &Vec<String>,
-    fields: &Fields,
-  ) -> TEResult<Vec<Self>> {
+  pub fn parse_fields(ctx: &ParseContext, fields: &Fields) -> TEResult<Vec<Self>> {
     fields
       .into_iter()
-      .map(|it| match &it.ty {
-        Type::Path(type_path) => {
-          let attr = parse_attributes(&it.attrs)?;
-
-          Ok(FieldInfo {
-            name: it.ident.as_ref().map(|it| it.to_string()),
-            ty: TypeInfo::parse_type_path(path, uses, local_items, it, &attr, type_path)?,
-            attr,
-          })
-        }
-        _ => Err(TEError::UnknownType(
-          it.ty.to_token_stream().to_string(),
-          it.ty.clone(),
-        )),
+      .filter(|it| is_cfg_enabled(ctx, &it.attrs))
+      .map(|it| {
+        let attr = parse_attributes(ctx, &it.attrs)?;
+        let ty = match TypeInfo::parse_type(ctx, &it.ty, &attr, &it.ty) {
+          // replaced by the helper or never exported, doesn't have to be known
+          Err(TEError::UnknownType(..))
+            if attr.is_with()
+              || attr.is_skipped(Direction::Serialize)
+                && attr.is_skipped(Direction::Deserialize) =>
+          {
+            TypeInfo::Custom("unknown".to_string())
+          }
+          ty => ty?,
+        };
+
+        Ok(FieldInfo {
+          name: it.ident.as_ref().map(|it| it.unraw().to_string()),
+          with: WithInfo::parse(ctx, &attr, &ty)?,
+          ty,
+          attr,
+        })
       })
       .collect::<TEResult<Vec<FieldInfo>>>()
   }
 
+  /// the type the field is exported as
+  pub fn ty_for(&self, direction: Direction) -> &TypeInfo {
+    self.with.get(direction).unwrap_or(&self.ty)
+  }
+
+  /// whether the field may be absent from the json,
+  /// see [AttributeInfo::is_optional], also a missing `Option` deserializes to `None`
+  /// unless it's deserialized by a custom function
+  pub fn is_optional(&self, container: &AttributeInfo, direction: Direction) -> bool {
+    self.attr.is_optional(container, direction)
+      || (direction == Direction::Deserialize
+        && !self.attr.with.is_set()
+        && !self.attr.deserialize_with.is_set()
+        && matches!(self.ty.see_through(), TypeInfo::Option(_)))
+  }
+
   pub fn to_ts_ast_named(
     &self,
+    container: &AttributeInfo,
     rename_all: Option<&RenameAll>,
+    direction: Direction,
   ) -> Option<TsAst<ast::TsTypeElement>> {
-    let name = rename_name(&self.attr, rename_all, self.name.as_ref())?;
+    let name = rename_name(&self.attr, rename_all, self.name.as_ref(), direction)?;
 
-    let ty_ast = self.ty.to_ts_ast();
+    let ty_ast = self.ty_for(direction).to_ts_ast(direction);
     let ts_ast = TsAst {
-      ast: create_property_type_element(create_expr_ident(name), ty_ast.ast),
+      ast: if self.is_optional(container, direction) {
+        create_optional_property_type_element(create_expr_key(name), ty_ast.ast)
+      } else {
+        create_property_type_element(create_expr_key(name), ty_ast.ast)
+      },
       dependencies: ty_ast.dependencies,
     };
 
     Some(ts_ast)
   }
 
-  pub fn to_ts_ast_unnamed(&self, rename_all: Option<&RenameAll>) -> TsAst<ast::TsTupleElement> {
-    let label = rename_name(&self.attr, rename_all, self.name.as_ref()).map(|it| {
+  /// the type to intersect with for a field with `#[serde(alias = "...")]` when deserializing,
+  /// it's accepted under any of its names, `None` if there is nothing to intersect with
+  pub fn to_ts_ast_aliased(
+    &self,
+    container: &AttributeInfo,
+    rename_all: Option<&RenameAll>,
+    direction: Direction,
+  ) -> Option<TsAst<ast::TsType>> {
+    if direction != Direction::Deserialize || self.attr.alias.is_empty() {
+      return None;
+    }
+
+    let name = rename_name(&self.attr, rename_all, self.name.as_ref(), direction)?;
+    let optional = self.is_optional(container, direction);
+
+    let ty_ast = self.ty_for(direction).to_ts_ast(direction);
+    let types = iter::once(name)
+      .chain(self.attr.alias.iter().cloned())
+      .map(|name| {
+        let member = if optional {
+          create_optional_property_type_element(create_expr_key(name), ty_ast.ast.clone())
+        } else {
+          create_property_type_element(create_expr_key(name), ty_ast.ast.clone())
+        };
+        Box::new(create_type_lit(vec![member]))
+      })
+      .collect();
+
+    Some(TsAst {
+      ast: create_type_array(types),
+      dependencies: ty_ast.dependencies,
+    })
+  }
+
+  /// the type to intersect with for a `#[serde(flatten)]` field
+  pub fn to_ts_ast_flattened(&self, direction: Direction) -> TsAst<ast::TsType> {
+    fn flattened(ty: &TypeInfo, direction: Direction) -> TsAst<ast::TsType> {
+      match ty.see_through() {
+        TypeInfo::Map(_, ty_v) | TypeInfo::EsMap(_, ty_v) => {
+          let ty_v_ast = ty_v.to_ts_ast(direction);
+          TsAst {
+            ast: create_index_signature_type_lit(ty_v_ast.ast),
+            dependencies: ty_v_ast.dependencies,
+          }
+        }
+        // none of the fields are present if it's none
+        TypeInfo::Option(ty) => {
+          let ts_ast = flattened(ty, direction);
+          TsAst {
+            ast: create_type_ref("Partial", Some(vec![Box::new(ts_ast.ast)])),
+            dependencies: ts_ast.dependencies,
+          }
+        }
+        ty => {
+          let ty_ast = ty.to_ts_ast(direction);
+          TsAst {
+            ast: *ty_ast.ast.type_ann,
+            dependencies: ty_ast.dependencies,
+          }
+        }
+      }
+    }
+
+    flattened(self.ty_for(direction), direction)
+  }
+
+  pub fn to_ts_ast_unnamed(
+    &self,
+    rename_all: Option<&RenameAll>,
+    direction: Direction,
+  ) -> TsAst<ast::TsTupleElement> {
+    let label = rename_name(&self.attr, rename_all, self.name.as_ref(), direction).map(|it| {
       ast::Pat::Ident(ast::BindingIdent {
         id: create_ident(it),
         type_ann: None,
       })
     });
 
-    let ty_ast = self.ty.to_ts_ast();
+    let ty_ast = self.ty_for(direction).to_ts_ast(direction);
     TsAst {
       ast: ast::TsTupleElement {
         span: Default::default(),
@@ -1,3 +1,4 @@
+use crate::item::alias_info::AliasInfo;
 use crate::item::enum_info::EnumInfo;
 use crate::item::struct_info::StructInfo;
 
@@ -5,6 +6,7 @@ use crate::item::struct_info::StructInfo;
 pub enum ItemInfo {
   Struct { processed: bool, item: StructInfo },
   Enum { processed: bool, item: EnumInfo },
+  Alias { processed: bool, item: AliasInfo },
 }
 
 impl ItemInfo {
@@ -12,6 +14,7 @@ impl ItemInfo {
     match self {
       ItemInfo::Struct { item, .. } => item.name.as_str(),
       ItemInfo::Enum { item, .. } => item.name.as_str(),
+      ItemInfo::Alias { item, .. } => item.name.as_str(),
     }
   }
 }
@@ -25,6 +28,15 @@ impl From<EnumInfo> for ItemInfo {
   }
 }
 
+impl From<AliasInfo> for ItemInfo {
+  fn from(value: AliasInfo) -> Self {
+    Self::Alias {
+      item: value,
+      processed: false,
+    }
+  }
+}
+
 impl From<StructInfo> for ItemInfo {
   fn from(value: StructInfo) -> Self {
     Self::Struct {
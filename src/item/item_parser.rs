@@ -1,35 +1,79 @@
-use log::debug;
-use syn::{Item, ItemEnum, ItemStruct};
+use log::{debug, error};
+use syn::{Attribute, Item, ItemEnum, ItemStruct, ItemType};
 
+use crate::config::Config;
+use crate::item::alias_info::AliasInfo;
+use crate::item::cfg::{expand_cfg_attr, is_cfg_enabled};
 use crate::item::enum_info::EnumInfo;
 use crate::item::item_info::ItemInfo;
+use crate::item::parse_context::ParseContext;
 use crate::item::struct_info::StructInfo;
 use crate::use_path::{PathRs, UsePath};
 use crate::{TEError, TEResult};
 
 #[derive(Debug, Clone)]
 pub struct ItemParser<'a> {
+  config: &'a Config,
   uses: &'a Vec<UsePath<PathRs>>,
   local_items: &'a Vec<String>,
 }
 
 impl<'a> ItemParser<'a> {
-  pub fn new(uses: &'a Vec<UsePath<PathRs>>, local_items: &'a Vec<String>) -> Self {
-    Self { uses, local_items }
+  pub fn new(
+    config: &'a Config,
+    uses: &'a Vec<UsePath<PathRs>>,
+    local_items: &'a Vec<String>,
+  ) -> Self {
+    Self {
+      config,
+      uses,
+      local_items,
+    }
+  }
+
+  fn context<'p>(&self, path: &'p UsePath<PathRs>) -> ParseContext<'p>
+  where
+    'a: 'p,
+  {
+    ParseContext::new(self.config, path, self.uses, self.local_items)
   }
 
   pub fn parse_item(&self, path: &UsePath<PathRs>, item: &Item) -> Option<TEResult<ItemInfo>> {
-    match item {
+    let (ident, attrs) = match item {
+      Item::Enum(it) => (&it.ident, &it.attrs),
+      Item::Struct(it) => (&it.ident, &it.attrs),
+      Item::Type(it) => (&it.ident, &it.attrs),
+      _ => return None,
+    };
+    if !is_cfg_enabled(&self.context(path), attrs) {
+      debug!("skipping {ident}, disabled by cfg");
+      return None;
+    }
+
+    let result = match item {
       Item::Enum(it) => {
         debug!("parsing enum {}", it.ident);
-        Some(self.parse_item_enum(path, it))
+        self.parse_item_enum(path, it)
       }
       Item::Struct(it) => {
         debug!("parsing struct {}", it.ident);
-        Some(self.parse_item_struct(path, it))
+        self.parse_item_struct(path, it)
+      }
+      Item::Type(it) => {
+        debug!("parsing type alias {}", it.ident);
+        self.parse_item_type(path, it)
+      }
+      _ => return None,
+    };
+
+    // failures of the other items are reported when they're used
+    if let Err(err) = &result {
+      if is_entry(&self.context(path), attrs) {
+        error!("unable to export entry {ident}: {err}");
       }
-      _ => None,
     }
+
+    Some(result)
   }
 
   pub fn parse_item_enum(
@@ -37,7 +81,7 @@ impl<'a> ItemParser<'a> {
     path: &UsePath<PathRs>,
     item_enum: &ItemEnum,
   ) -> TEResult<ItemInfo> {
-    EnumInfo::parse_item_enum(path, self.uses, self.local_items, item_enum)
+    EnumInfo::parse_item_enum(&self.context(path), item_enum)
       .map(ItemInfo::from)
       .map_err(|err| TEError::ParseItemFailed(item_enum.ident.to_string(), err.into()))
   }
@@ -47,8 +91,39 @@ impl<'a> ItemParser<'a> {
     path: &UsePath<PathRs>,
     item_struct: &ItemStruct,
   ) -> TEResult<ItemInfo> {
-    StructInfo::parse_item_struct(path, self.uses, self.local_items, item_struct)
+    StructInfo::parse_item_struct(&self.context(path), item_struct)
       .map(ItemInfo::from)
       .map_err(|err| TEError::ParseItemFailed(item_struct.ident.to_string(), err.into()))
   }
+
+  pub fn parse_item_type(
+    &self,
+    path: &UsePath<PathRs>,
+    item_type: &ItemType,
+  ) -> TEResult<ItemInfo> {
+    AliasInfo::parse_item_type(&self.context(path), item_type)
+      .map(ItemInfo::from)
+      .map_err(|err| TEError::ParseItemFailed(item_type.ident.to_string(), err.into()))
+  }
+}
+
+/// whether there is `#[te(entry)]`, without parsing the other attributes which may be the reason
+/// the item can't be parsed
+fn is_entry(ctx: &ParseContext, attrs: &[Attribute]) -> bool {
+  expand_cfg_attr(ctx, attrs)
+    .iter()
+    .filter(|it| it.path().is_ident("te"))
+    .any(|attr| {
+      let mut entry = false;
+      let _ = attr.parse_nested_meta(|meta| {
+        entry |= meta.path.is_ident("entry");
+        if meta.input.peek(syn::Token![=]) {
+          meta.value()?.parse::<syn::Expr>()?;
+        } else if meta.input.peek(syn::token::Paren) {
+          meta.input.parse::<proc_macro2::Group>()?;
+        }
+        Ok(())
+      });
+      entry
+    })
 }
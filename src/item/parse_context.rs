@@ -0,0 +1,70 @@
+/*
+ * Copyright 2021-2023 Golden_Water
+ * SPDX-License-Identifier: AGPL-3.0-only
+ */
+
+use proc_macro2::Span;
+use syn::{GenericParam, Generics};
+
+use crate::config::Config;
+use crate::use_path::{PathRs, UsePath};
+
+/// everything a parser needs to know about the surrounding of the item it is parsing
+#[derive(Debug, Clone)]
+pub struct ParseContext<'a> {
+  pub config: &'a Config,
+  pub path: &'a UsePath<PathRs>,
+  pub uses: &'a Vec<UsePath<PathRs>>,
+  pub local_items: &'a Vec<String>,
+  /// type parameters in scope
+  pub generics: Vec<String>,
+}
+
+impl<'a> ParseContext<'a> {
+  pub fn new(
+    config: &'a Config,
+    path: &'a UsePath<PathRs>,
+    uses: &'a Vec<UsePath<PathRs>>,
+    local_items: &'a Vec<String>,
+  ) -> Self {
+    Self {
+      config,
+      path,
+      uses,
+      local_items,
+      generics: vec![],
+    }
+  }
+
+  pub fn with_generics(mut self, generics: Vec<String>) -> Self {
+    self.generics = generics;
+    self
+  }
+
+  pub fn is_generic(&self, name: &str) -> bool {
+    self.generics.iter().any(|it| it.eq(name))
+  }
+
+  /// `file:line:column` of the span, for messages
+  pub fn location(&self, span: Span) -> String {
+    let location = span.start();
+    format!(
+      "{}:{}:{}",
+      self.path.to_fs().to_path_buf_with_ext().display(),
+      location.line,
+      location.column + 1
+    )
+  }
+}
+
+/// names of the type parameters, lifetimes and const generics are ignored
+pub fn parse_generics(generics: &Generics) -> Vec<String> {
+  generics
+    .params
+    .iter()
+    .filter_map(|it| match it {
+      GenericParam::Type(it) => Some(it.ident.to_string()),
+      _ => None,
+    })
+    .collect()
+}
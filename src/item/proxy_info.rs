@@ -0,0 +1,46 @@
+/*
+ * Copyright 2021-2023 Golden_Water
+ * SPDX-License-Identifier: AGPL-3.0-only
+ */
+
+use syn::Type;
+
+use crate::item::attribute_info::{AttributeInfo, Direction};
+use crate::item::parse_context::ParseContext;
+use crate::item::type_info::TypeInfo;
+use crate::TEResult;
+
+/// the types an item is converted through by `#[serde(into, from, try_from)]`
+#[derive(Debug, Clone, Default)]
+pub struct ProxyInfo {
+  /// `into`, used when serializing
+  pub into: Option<TypeInfo>,
+  /// `from` or `try_from`, used when deserializing
+  pub from: Option<TypeInfo>,
+}
+
+impl ProxyInfo {
+  pub fn parse(ctx: &ParseContext, attr: &AttributeInfo) -> TEResult<Self> {
+    let parse = |ty: Option<&String>| {
+      ty.map(|ty| {
+        let ty = syn::parse_str::<Type>(ty)?;
+        TypeInfo::parse_type(ctx, &ty, &AttributeInfo::default(), &ty)
+      })
+      .transpose()
+    };
+
+    Ok(Self {
+      into: parse(attr.into.get())?,
+      from: parse(attr.from.get().or(attr.try_from.get()))?,
+    })
+  }
+
+  /// the type to export instead of the item itself,
+  /// [Direction::Both] is the serialized form so only `into` applies
+  pub fn get(&self, direction: Direction) -> Option<&TypeInfo> {
+    match direction {
+      Direction::Both | Direction::Serialize => self.into.as_ref(),
+      Direction::Deserialize => self.from.as_ref(),
+    }
+  }
+}
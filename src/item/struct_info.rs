@@ -8,34 +8,39 @@ use std::collections::HashSet;
 use swc_core::ecma::ast;
 use syn::ItemStruct;
 
-use crate::item::attribute_info::{parse_attributes, AttributeInfo, RenameAll};
+use crate::item::attribute_info::{parse_attributes, AttributeInfo, Direction};
 use crate::item::field_info::FieldInfo;
-use crate::use_path::{PathRs, UsePath};
+use crate::item::parse_context::{parse_generics, ParseContext};
+use crate::item::proxy_info::ProxyInfo;
+use crate::item::type_info::TypeInfo;
 use crate::utils::rename_name;
 use crate::utils::ts_ast_utils::{
   create_ident, create_keyword_type, create_module_decl_item, create_type_alias_decl,
-  create_type_lit,
+  create_type_intersection, create_type_lit, create_type_param_decl,
 };
 use crate::{TEResult, TsAst};
 
 #[derive(Debug, Clone)]
 pub struct StructInfo {
   pub name: String,
+  pub generics: Vec<String>,
   pub fields: Vec<FieldInfo>,
   pub attr: AttributeInfo,
+  pub proxy: ProxyInfo,
 }
 
 impl StructInfo {
-  pub fn parse_item_struct(
-    path: &UsePath<PathRs>,
-    uses: &Vec<UsePath<PathRs>>,
-    local_items: &Vec<String>,
-    item_struct: &ItemStruct,
-  ) -> TEResult<Self> {
+  pub fn parse_item_struct(ctx: &ParseContext, item_struct: &ItemStruct) -> TEResult<Self> {
+    let generics = parse_generics(&item_struct.generics);
+    let ctx = ctx.clone().with_generics(generics.clone());
+    let attr = parse_attributes(&ctx, &item_struct.attrs)?;
+
     Ok(Self {
       name: item_struct.ident.to_string(),
-      fields: FieldInfo::parse_fields(path, uses, local_items, &item_struct.fields)?,
-      attr: parse_attributes(&item_struct.attrs)?,
+      generics,
+      fields: FieldInfo::parse_fields(&ctx, &item_struct.fields)?,
+      proxy: ProxyInfo::parse(&ctx, &attr)?,
+      attr,
     })
   }
 
@@ -68,36 +73,89 @@ impl StructInfo {
     false
   }
 
-  pub fn to_ts_ast(&self) -> TsAst<ast::ModuleItem> {
+  /// the field a `#[serde(transparent)]` struct is serialized as,
+  /// the only one that isn't skipped, ignoring `PhantomData`
+  pub fn transparent_field(&self, direction: Direction) -> Option<&FieldInfo> {
+    let mut fields = self
+      .fields
+      .iter()
+      .filter(|it| !it.attr.is_skipped(direction))
+      .collect::<Vec<_>>();
+
+    if fields.len() > 1 {
+      fields.retain(|it| !matches!(it.ty, TypeInfo::PhantomData));
+    }
+
+    fields.first().copied()
+  }
+
+  pub fn to_ts_ast(&self, direction: Direction) -> TsAst<ast::ModuleItem> {
     let mut dependencies = HashSet::new();
 
-    let rename_all: Option<RenameAll> = self.attr.rename_all.clone().into();
+    let rename_all = self.attr.rename_all(direction);
 
-    let type_ann = if self.is_normal_struct() {
+    let type_ann = if let Some(ty) = self.proxy.get(direction) {
+      let ts_ast = ty.to_ts_ast(direction);
+      dependencies.extend(ts_ast.dependencies);
+      *ts_ast.ast.type_ann
+    } else if self.attr.transparent.is_set() {
+      if let Some(field) = self.transparent_field(direction) {
+        let ts_ast = field.ty_for(direction).to_ts_ast(direction);
+        dependencies.extend(ts_ast.dependencies);
+        *ts_ast.ast.type_ann
+      } else {
+        create_keyword_type(ast::TsKeywordTypeKind::TsNullKeyword)
+      }
+    } else if self.is_normal_struct() {
       let mut members = vec![];
+      let mut flattened = vec![];
 
       for field in &self.fields {
-        if field.attr.is_skipped() {
+        if field.attr.is_skipped(direction) {
+          continue;
+        }
+        if field.attr.flatten.is_set() {
+          let ts_ast = field.to_ts_ast_flattened(direction);
+
+          flattened.push(Box::new(ts_ast.ast));
+          dependencies.extend(ts_ast.dependencies);
+          continue;
+        }
+        if let Some(ts_ast) = field.to_ts_ast_aliased(&self.attr, rename_all, direction) {
+          flattened.push(Box::new(ts_ast.ast));
+          dependencies.extend(ts_ast.dependencies);
           continue;
         }
         let ts_ast = field
-          .to_ts_ast_named(rename_all.as_ref())
+          .to_ts_ast_named(&self.attr, rename_all, direction)
           .expect("unexpect unnamed field inside a normal struct");
 
         members.push(ts_ast.ast);
         dependencies.extend(ts_ast.dependencies);
       }
 
-      create_type_lit(members)
+      if flattened.is_empty() {
+        create_type_lit(members)
+      } else {
+        if !members.is_empty() {
+          flattened.insert(0, Box::new(create_type_lit(members)));
+        }
+
+        if flattened.len() == 1 {
+          *flattened.remove(0)
+        } else {
+          create_type_intersection(flattened)
+        }
+      }
     } else if self.is_tuple_struct() {
       if self.fields.len() > 1 {
         let mut elem_types = vec![];
 
         for field in &self.fields {
-          if field.attr.is_skipped() {
+          if field.attr.is_skipped(direction) {
             continue;
           }
-          let ts_ast = field.to_ts_ast_unnamed(rename_all.as_ref());
+          let ts_ast = field.to_ts_ast_unnamed(rename_all, direction);
 
           elem_types.push(ts_ast.ast);
           dependencies.extend(ts_ast.dependencies);
@@ -108,7 +166,7 @@ impl StructInfo {
           elem_types,
         })
       } else {
-        let ts_ast = self.fields[0].ty.to_ts_ast();
+        let ts_ast = self.fields[0].ty_for(direction).to_ts_ast(direction);
         dependencies.extend(ts_ast.dependencies);
         *ts_ast.ast.type_ann
       }
@@ -116,8 +174,11 @@ impl StructInfo {
       create_keyword_type(ast::TsKeywordTypeKind::TsNullKeyword)
     };
 
+    let name = rename_name(&self.attr, None, Some(&self.name), direction).unwrap();
+
     let decl = create_type_alias_decl(
-      create_ident(rename_name(&self.attr, None, Some(&self.name)).unwrap()),
+      create_ident(direction.type_name(&name)),
+      create_type_param_decl(&self.generics),
       type_ann,
     );
 
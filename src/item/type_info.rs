@@ -8,22 +8,45 @@ use std::str::FromStr;
 
 use swc_core::ecma::ast;
 use syn::__private::ToTokens;
-use syn::{Field, GenericArgument, PathArguments, Type, TypePath};
+use syn::{Expr, ExprLit, GenericArgument, Lit, Path, PathArguments, PathSegment, Type, TypePath};
 
-use crate::item::attribute_info::AttributeInfo;
+use crate::config::{BigIntStyle, MapStyle, TypeMapping};
+use crate::item::attribute_info::{AttributeInfo, Direction};
+use crate::item::parse_context::ParseContext;
 use crate::use_path::{PathRs, UsePath};
-use crate::utils::ts_ast_utils::{create_keyword_type, create_type_array, create_type_ref};
+use crate::utils::ts_ast_utils::{
+  create_expr_ident, create_keyword_type, create_number_str_type, create_property_type_element,
+  create_type_array, create_type_lit, create_type_ref, create_type_tuple,
+};
 use crate::{TEError, TEResult, TsAst};
 
 #[derive(Debug, Clone)]
 pub enum TypeInfo {
-  Normal(UsePath<PathRs>),
+  /// a user defined type with its generic arguments
+  Normal(UsePath<PathRs>, Vec<TypeInfo>),
+  /// a type parameter of the item that contains it
+  Generic(String),
   Option(Box<TypeInfo>),
   Vec(Box<TypeInfo>),
+  /// a map serialized as json object
   Map(Box<TypeInfo>, Box<TypeInfo>),
+  /// a map exported as `Map<K, V>`, see [MapStyle::EsMap]
+  EsMap(Box<TypeInfo>, Box<TypeInfo>),
+  /// wrappers serialized as their inner value, e.g. `Box<T>`, `Arc<T>` or `RefCell<T>`
   Box(Box<TypeInfo>),
+  Tuple(Vec<TypeInfo>),
+  /// fixed-size array, the length is only known when it's a literal that serde serializes as tuple
+  Array(Box<TypeInfo>, Option<usize>),
   /// types from same file will also gets merged into this variant
   Custom(String),
+  /// externally tagged, `{ Ok: T } | { Err: E }`
+  Result(Box<TypeInfo>, Box<TypeInfo>),
+  /// `()`, serialized as `null`
+  Unit,
+  /// serialized as `null` like unit
+  PhantomData,
+  /// `Infallible` or `!`, can never be serialized
+  Never,
   Bool,
   Number,
   BigInt,
@@ -31,24 +54,106 @@ pub enum TypeInfo {
 }
 
 impl TypeInfo {
+  /// `full_ty` is the whole type of the field or alias, for error reporting
+  pub fn parse_type(
+    ctx: &ParseContext,
+    full_ty: &Type,
+    attr: &AttributeInfo,
+    ty: &Type,
+  ) -> TEResult<TypeInfo> {
+    match ty {
+      Type::Path(type_path) => Self::parse_type_path(ctx, full_ty, attr, type_path),
+      Type::Paren(type_paren) => Self::parse_type(ctx, full_ty, attr, &type_paren.elem),
+      Type::Group(type_group) => Self::parse_type(ctx, full_ty, attr, &type_group.elem),
+      Type::Reference(type_reference) => Self::parse_type(ctx, full_ty, attr, &type_reference.elem),
+      Type::Slice(type_slice) => Ok(TypeInfo::Vec(Box::new(Self::parse_type(
+        ctx,
+        full_ty,
+        attr,
+        &type_slice.elem,
+      )?))),
+      Type::Tuple(type_tuple) if type_tuple.elems.is_empty() => Ok(TypeInfo::Unit),
+      Type::Never(_) => Ok(TypeInfo::Never),
+      Type::Tuple(type_tuple) => Ok(TypeInfo::Tuple(
+        type_tuple
+          .elems
+          .iter()
+          .map(|it| Self::parse_type(ctx, full_ty, attr, it))
+          .collect::<TEResult<Vec<_>>>()?,
+      )),
+      Type::Array(type_array) => Ok(TypeInfo::Array(
+        Box::new(Self::parse_type(ctx, full_ty, attr, &type_array.elem)?),
+        parse_array_len(&type_array.len),
+      )),
+      _ => {
+        if let Some(retype) = attr.retype.get() {
+          Ok(TypeInfo::Custom(retype.clone()))
+        } else {
+          Err(unknown_type(full_ty))
+        }
+      }
+    }
+  }
+
   pub fn parse_type_path(
-    path: &UsePath<PathRs>,
-    uses: &Vec<UsePath<PathRs>>,
-    local_items: &Vec<String>,
-    field: &Field,
+    ctx: &ParseContext,
+    full_ty: &Type,
     attr: &AttributeInfo,
     type_path: &TypePath,
   ) -> TEResult<TypeInfo> {
+    let parse_generic_args = |path_segment: &PathSegment| -> TEResult<Vec<TypeInfo>> {
+      match parse_path_generics(&path_segment.arguments) {
+        Ok(generics) => generics
+          .iter()
+          .map(|it| Self::parse_type(ctx, full_ty, attr, it))
+          .collect(),
+        Err(_) => Ok(vec![]),
+      }
+    };
+
+    // region type parameters
+    if let Some(ident) = type_path.path.get_ident() {
+      if ctx.is_generic(&ident.to_string()) {
+        return Ok(TypeInfo::Generic(ident.to_string()));
+      }
+    }
+    // endregion
+
     // region imported
-    let path_segment = match UsePath::<PathRs>::resolve_type_from_uses(uses, &type_path.path) {
-      Ok(path) => return Ok(TypeInfo::Normal(path)),
+    let path_segment = match UsePath::<PathRs>::resolve_type_from_uses(ctx.uses, &type_path.path) {
+      Ok(path) => {
+        let path_segment = type_path.path.segments.last().unwrap();
+        return Ok(TypeInfo::Normal(path, parse_generic_args(path_segment)?));
+      }
       Err(path_segment) => path_segment,
     };
     // endregion
 
     let type_name = path_segment.ident.to_string();
+    // region local
+    // a local item shadows the std types with the same name, e.g. `type Result<T> = ...`
+    if type_path.path.segments.len() == 1
+      && ctx.local_items.contains(&type_name)
+      && !attr.retype.is_set()
+    {
+      return Ok(TypeInfo::Normal(
+        ctx.path.clone().with_name(type_name).with_local_use(true),
+        parse_generic_args(path_segment)?,
+      ));
+    }
+    // endregion
+
     // region primitives
     if let Ok(ty) = TypeInfo::from_str(&type_name) {
+      if let TypeInfo::BigInt = ty {
+        let style = attr.big_int.get().unwrap_or(&ctx.config.big_int);
+
+        return Ok(match style {
+          BigIntStyle::Number => TypeInfo::Number,
+          BigIntStyle::BigInt => TypeInfo::BigInt,
+          BigIntStyle::String => TypeInfo::String,
+        });
+      }
       return Ok(ty);
     }
     // endregion
@@ -61,25 +166,36 @@ impl TypeInfo {
         check_generics_length(&type_name, $generics_args.len(), $num)?;
         Some(TypeInfo::$name(
           $(
-            Box::from(Self::parse_type_path(
-              path,
-              uses,
-              local_items,
-              field,
-              attr,
-              &$generics_args[$idx],
-            )?),
+            Box::from(Self::parse_type(ctx, full_ty, attr, &$generics_args[$idx])?),
           )*
         ))
       }};
     }
 
+    let map_style = ctx.config.map_style;
+
     let type_info = match generics {
-      Ok(generics) => match type_name.as_str() {
+      Ok(mut generics) => match type_name.as_str() {
         "Option" => parse_with_generics!(generics, 1, Option < 0),
-        "Vec" => parse_with_generics!(generics, 1, Vec < 0),
-        "HashMap" => parse_with_generics!(generics, 2, Map < 0, 1),
-        "Box" => parse_with_generics!(generics, 1, Box < 0),
+        "Result" => parse_with_generics!(generics, 2, Result < 0, 1),
+        "Vec" | "VecDeque" | "LinkedList" | "BinaryHeap" | "BTreeSet" => {
+          parse_with_generics!(generics, 1, Vec < 0)
+        }
+        "HashSet" | "IndexSet" => {
+          // the hasher doesn't affect the serialized form
+          generics.truncate(1);
+          parse_with_generics!(generics, 1, Vec < 0)
+        }
+        "BTreeMap" | "HashMap" | "IndexMap" => {
+          generics.truncate(2);
+          match map_style {
+            MapStyle::Json => parse_with_generics!(generics, 2, Map < 0, 1),
+            MapStyle::EsMap => parse_with_generics!(generics, 2, EsMap < 0, 1),
+          }
+        }
+        // serialized as the inner value
+        "Box" | "Cow" | "Rc" | "Arc" | "Cell" | "RefCell" | "Mutex" | "RwLock" | "Wrapping"
+        | "Reverse" => parse_with_generics!(generics, 1, Box < 0),
         _ => None,
       },
       Err(_) => None,
@@ -92,28 +208,83 @@ impl TypeInfo {
 
     if let Some(retype) = attr.retype.get() {
       Ok(TypeInfo::Custom(retype.clone()))
-    } else if local_items.contains(&type_name) {
+    } else if ctx.local_items.contains(&type_name) {
       Ok(TypeInfo::Normal(
-        path.clone().with_name(type_name).with_local_use(true),
+        ctx.path.clone().with_name(type_name).with_local_use(true),
+        parse_generic_args(path_segment)?,
       ))
+    } else if let Some(mapping) = ctx.config.find_type_mapping(
+      &type_path
+        .path
+        .segments
+        .iter()
+        .map(|it| it.ident.to_string())
+        .collect::<Vec<_>>(),
+    ) {
+      Ok(mapping.into())
+    } else if let Some(ty) = ctx
+      .config
+      .builtin_types
+      .then(|| builtin_type(&type_path.path))
+      .flatten()
+    {
+      Ok(ty)
     } else {
-      Err(TEError::UnknownType(
-        field.ty.to_token_stream().to_string(),
-        field.ty.clone(),
-      ))
+      Err(unknown_type(full_ty))
+    }
+  }
+
+  /// the type this is serialized as, skipping transparent wrappers
+  pub fn see_through(&self) -> &TypeInfo {
+    match self {
+      TypeInfo::Box(ty) => ty.see_through(),
+      _ => self,
     }
   }
 
-  pub fn to_ts_ast(&self) -> TsAst<ast::TsTypeAnn> {
+  /// whether serde can serialize it as a map, which is required for the content of a newtype
+  /// variant that is internally tagged, unknown types are assumed to be structs
+  pub fn is_object_like(&self) -> bool {
+    matches!(
+      self.see_through(),
+      TypeInfo::Normal(..)
+        | TypeInfo::Generic(_)
+        | TypeInfo::Custom(_)
+        | TypeInfo::Map(..)
+        | TypeInfo::EsMap(..)
+        | TypeInfo::Unit
+        | TypeInfo::PhantomData
+    )
+  }
+
+  pub fn to_ts_ast(&self, direction: Direction) -> TsAst<ast::TsTypeAnn> {
     let mut dependencies = HashSet::new();
 
     let ts_type = match self {
-      TypeInfo::Normal(rs_path) => {
+      TypeInfo::Normal(rs_path, generics) => {
         dependencies.insert(rs_path.clone());
-        create_type_ref(&rs_path.name, None)
+
+        let params = generics
+          .iter()
+          .map(|it| {
+            let ty_ast = it.to_ts_ast(direction);
+            dependencies.extend(ty_ast.dependencies);
+            ty_ast.ast.type_ann
+          })
+          .collect::<Vec<_>>();
+
+        // types from ts modules are imported as is
+        let name = if rs_path.module.is_some() {
+          rs_path.name.clone()
+        } else {
+          direction.type_name(&rs_path.name)
+        };
+
+        create_type_ref(name, (!params.is_empty()).then_some(params))
       }
+      TypeInfo::Generic(name) => create_type_ref(name, None),
       TypeInfo::Option(ty) => {
-        let ty_ast = ty.to_ts_ast();
+        let ty_ast = ty.to_ts_ast(direction);
         dependencies.extend(ty_ast.dependencies);
 
         create_type_array(vec![
@@ -122,14 +293,46 @@ impl TypeInfo {
         ])
       }
       TypeInfo::Vec(ty) => {
-        let ty_ast = ty.to_ts_ast();
+        let ty_ast = ty.to_ts_ast(direction);
         dependencies.extend(ty_ast.dependencies);
 
         create_type_ref("Array", Some(vec![ty_ast.ast.type_ann]))
       }
       TypeInfo::Map(ty_k, ty_v) => {
-        let ty_k_ast = ty_k.to_ts_ast();
-        let ty_v_ast = ty_v.to_ts_ast();
+        let ty_k_ast = ty_k.to_ts_ast(direction);
+        let ty_v_ast = ty_v.to_ts_ast(direction);
+        dependencies.extend(ty_k_ast.dependencies);
+        dependencies.extend(ty_v_ast.dependencies);
+
+        // json object keys are always strings
+        match ty_k.see_through() {
+          TypeInfo::Number | TypeInfo::BigInt => create_type_ref(
+            "Record",
+            Some(vec![
+              Box::new(create_number_str_type()),
+              ty_v_ast.ast.type_ann,
+            ]),
+          ),
+          // most likely unit enums, not every variant has to be present
+          TypeInfo::Normal(..) | TypeInfo::Custom(_) => create_type_ref(
+            "Partial",
+            Some(vec![Box::new(create_type_ref(
+              "Record",
+              Some(vec![ty_k_ast.ast.type_ann, ty_v_ast.ast.type_ann]),
+            ))]),
+          ),
+          _ => create_type_ref(
+            "Record",
+            Some(vec![
+              Box::new(create_keyword_type(ast::TsKeywordTypeKind::TsStringKeyword)),
+              ty_v_ast.ast.type_ann,
+            ]),
+          ),
+        }
+      }
+      TypeInfo::EsMap(ty_k, ty_v) => {
+        let ty_k_ast = ty_k.to_ts_ast(direction);
+        let ty_v_ast = ty_v.to_ts_ast(direction);
         dependencies.extend(ty_k_ast.dependencies);
         dependencies.extend(ty_v_ast.dependencies);
 
@@ -138,8 +341,51 @@ impl TypeInfo {
           Some(vec![ty_k_ast.ast.type_ann, ty_v_ast.ast.type_ann]),
         )
       }
-      TypeInfo::Box(ty) => return ty.to_ts_ast(),
+      TypeInfo::Box(ty) => return ty.to_ts_ast(direction),
+      TypeInfo::Tuple(types) => {
+        let elem_types = types
+          .iter()
+          .map(|it| {
+            let ty_ast = it.to_ts_ast(direction);
+            dependencies.extend(ty_ast.dependencies);
+            ty_ast.ast.type_ann
+          })
+          .collect();
+
+        create_type_tuple(elem_types)
+      }
+      TypeInfo::Array(ty, len) => {
+        let ty_ast = ty.to_ts_ast(direction);
+        dependencies.extend(ty_ast.dependencies);
+
+        if let Some(len) = len {
+          create_type_tuple(vec![ty_ast.ast.type_ann; *len])
+        } else {
+          create_type_ref("Array", Some(vec![ty_ast.ast.type_ann]))
+        }
+      }
       TypeInfo::Custom(ty) => create_type_ref(ty, None),
+      TypeInfo::Result(ty_ok, ty_err) => {
+        let ty_ok_ast = ty_ok.to_ts_ast(direction);
+        let ty_err_ast = ty_err.to_ts_ast(direction);
+        dependencies.extend(ty_ok_ast.dependencies);
+        dependencies.extend(ty_err_ast.dependencies);
+
+        create_type_array(vec![
+          Box::new(create_type_lit(vec![create_property_type_element(
+            create_expr_ident("Ok"),
+            ty_ok_ast.ast,
+          )])),
+          Box::new(create_type_lit(vec![create_property_type_element(
+            create_expr_ident("Err"),
+            ty_err_ast.ast,
+          )])),
+        ])
+      }
+      TypeInfo::Unit | TypeInfo::PhantomData => {
+        create_keyword_type(ast::TsKeywordTypeKind::TsNullKeyword)
+      }
+      TypeInfo::Never => create_keyword_type(ast::TsKeywordTypeKind::TsNeverKeyword),
       TypeInfo::Bool => create_keyword_type(ast::TsKeywordTypeKind::TsBooleanKeyword),
       TypeInfo::Number => create_keyword_type(ast::TsKeywordTypeKind::TsNumberKeyword),
       TypeInfo::BigInt => create_keyword_type(ast::TsKeywordTypeKind::TsBigIntKeyword),
@@ -156,15 +402,37 @@ impl TypeInfo {
   }
 }
 
+impl From<&TypeMapping> for TypeInfo {
+  fn from(mapping: &TypeMapping) -> Self {
+    match mapping {
+      TypeMapping::Type(ty) | TypeMapping::Import { ty, import: None } => {
+        TypeInfo::Custom(ty.clone())
+      }
+      TypeMapping::Import {
+        ty,
+        import: Some(import),
+      } => TypeInfo::Normal(
+        UsePath::<PathRs>::new(vec![], ty.clone(), None).with_module(Some(import.clone())),
+        vec![],
+      ),
+    }
+  }
+}
+
 impl FromStr for TypeInfo {
   type Err = ();
 
   fn from_str(s: &str) -> Result<Self, Self::Err> {
     match s {
       "bool" => Ok(Self::Bool),
-      "u8" | "u16" | "u32" | "i8" | "i16" | "i32" | "f32" | "f64" => Ok(Self::Number),
-      "u64" | "i64" | "usize" | "isize" => Ok(Self::BigInt),
-      "String" | "char" => Ok(Self::String),
+      "u8" | "u16" | "u32" | "i8" | "i16" | "i32" | "f32" | "f64" | "NonZeroU8" | "NonZeroU16"
+      | "NonZeroU32" | "NonZeroI8" | "NonZeroI16" | "NonZeroI32" => Ok(Self::Number),
+      // mapped by BigIntStyle
+      "u64" | "i64" | "usize" | "isize" | "u128" | "i128" | "NonZeroU64" | "NonZeroI64"
+      | "NonZeroUsize" | "NonZeroIsize" | "NonZeroU128" | "NonZeroI128" => Ok(Self::BigInt),
+      "String" | "str" | "char" => Ok(Self::String),
+      "PhantomData" => Ok(Self::PhantomData),
+      "Infallible" => Ok(Self::Never),
       _ => Err(()),
     }
   }
@@ -182,7 +450,54 @@ fn check_generics_length(name: &str, actual: usize, expected: usize) -> TEResult
   }
 }
 
-fn parse_path_generics(path_arguments: &PathArguments) -> Result<Vec<TypePath>, ()> {
+/// well-known third-party types and their serde json representation
+fn builtin_type(path: &Path) -> Option<TypeInfo> {
+  let name = path.segments.last()?.ident.to_string();
+
+  let (crates, ty): (&[&str], _) = match name.as_str() {
+    "DateTime" | "NaiveDateTime" | "NaiveDate" | "NaiveTime" => (&["chrono"], TypeInfo::String),
+    "OffsetDateTime" | "PrimitiveDateTime" | "Date" | "Time" | "UtcOffset" => {
+      (&["time"], TypeInfo::String)
+    }
+    "Uuid" => (&["uuid"], TypeInfo::String),
+    "Url" => (&["url"], TypeInfo::String),
+    "Decimal" => (&["rust_decimal"], TypeInfo::String),
+    "BigDecimal" => (&["bigdecimal"], TypeInfo::String),
+    "Value" => (&["serde_json"], TypeInfo::Custom("unknown".to_string())),
+    _ => return None,
+  };
+
+  // only check the crate if the path is qualified
+  if path.segments.len() > 1 {
+    let crate_name = path.segments.first()?.ident.to_string();
+    if !crates.contains(&crate_name.as_str()) {
+      return None;
+    }
+  }
+
+  Some(ty)
+}
+
+fn unknown_type(full_ty: &Type) -> TEError {
+  TEError::UnknownType(
+    full_ty.to_token_stream().to_string(),
+    Box::new(full_ty.clone()),
+  )
+}
+
+/// serde only serializes arrays up to 32 elements as tuple
+fn parse_array_len(len: &Expr) -> Option<usize> {
+  if let Expr::Lit(ExprLit {
+    lit: Lit::Int(len), ..
+  }) = len
+  {
+    len.base10_parse::<usize>().ok().filter(|it| *it <= 32)
+  } else {
+    None
+  }
+}
+
+pub(crate) fn parse_path_generics(path_arguments: &PathArguments) -> Result<Vec<Type>, ()> {
   let generics = match path_arguments {
     PathArguments::AngleBracketed(generics) => Ok(generics),
     _ => Err(()),
@@ -191,9 +506,10 @@ fn parse_path_generics(path_arguments: &PathArguments) -> Result<Vec<TypePath>,
   generics
     .args
     .iter()
+    .filter(|it| !matches!(it, GenericArgument::Lifetime(_)))
     .map(|it| {
-      if let GenericArgument::Type(Type::Path(type_path)) = it {
-        return Ok(type_path.clone());
+      if let GenericArgument::Type(ty) = it {
+        return Ok(ty.clone());
       }
       Err(())
     })
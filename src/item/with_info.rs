@@ -0,0 +1,245 @@
+/*
+ * Copyright 2021-2023 Golden_Water
+ * SPDX-License-Identifier: AGPL-3.0-only
+ */
+
+use log::warn;
+use syn::{Path, Type};
+
+use crate::item::attribute_info::{AttributeInfo, Direction};
+use crate::item::parse_context::ParseContext;
+use crate::item::type_info::{parse_path_generics, TypeInfo};
+use crate::utils::match_path;
+use crate::TEResult;
+
+/// the types a field is serialized and deserialized as when it's customized by
+/// `#[serde(with, serialize_with, deserialize_with)]` or `#[serde_as(as = "...")]`
+#[derive(Debug, Clone, Default)]
+pub struct WithInfo {
+  pub serialize: Option<TypeInfo>,
+  pub deserialize: Option<TypeInfo>,
+}
+
+impl WithInfo {
+  /// `ty` is the type of the field
+  pub fn parse(ctx: &ParseContext, attr: &AttributeInfo, ty: &TypeInfo) -> TEResult<Self> {
+    let serde_as = match attr.serde_as.get() {
+      Some(serde_as) => Some(parse_serde_as(ctx, &syn::parse_str(serde_as)?, ty)?),
+      None => None,
+    };
+
+    let with = |path: Option<&String>| {
+      path
+        .and_then(|path| {
+          let ty = find_with(ctx, path);
+          if ty.is_none() {
+            warn!("unknown serde helper {path}, using the type of the field");
+          }
+          ty
+        })
+        .or_else(|| serde_as.clone())
+    };
+
+    Ok(Self {
+      serialize: with(attr.serialize_with.get().or(attr.with.get())),
+      deserialize: with(attr.deserialize_with.get().or(attr.with.get())),
+    })
+  }
+
+  /// the type to export instead of the type of the field,
+  /// [Direction::Both] is the serialized form so only the serializing helper applies
+  pub fn get(&self, direction: Direction) -> Option<&TypeInfo> {
+    match direction {
+      Direction::Both | Direction::Serialize => self.serialize.as_ref(),
+      Direction::Deserialize => self.deserialize.as_ref(),
+    }
+  }
+}
+
+/// the type of a `with` module or a `serialize_with`/`deserialize_with` function
+fn find_with(ctx: &ParseContext, path: &str) -> Option<TypeInfo> {
+  let mut path = path.split("::").map(str::to_string).collect::<Vec<_>>();
+  // `serialize_with = "module::serialize"` is the same as `with = "module"`
+  if path.len() > 1 && matches!(path.last().unwrap().as_str(), "serialize" | "deserialize") {
+    path.pop();
+  }
+
+  ctx
+    .config
+    .find_with_mapping(&path)
+    .map(TypeInfo::from)
+    .or_else(|| {
+      ctx
+        .config
+        .builtin_types
+        .then(|| builtin_with(&path))
+        .flatten()
+    })
+}
+
+/// `as_ty` is the type in `#[serde_as(as = "...")]`, `ty` is the type it applies to,
+/// which `_` stands for
+fn parse_serde_as(ctx: &ParseContext, as_ty: &Type, ty: &TypeInfo) -> TEResult<TypeInfo> {
+  let path = match as_ty {
+    Type::Infer(_) => return Ok(ty.clone()),
+    Type::Tuple(as_tuple) => {
+      return if let TypeInfo::Tuple(types) = ty.see_through() {
+        Ok(TypeInfo::Tuple(
+          as_tuple
+            .elems
+            .iter()
+            .zip(types)
+            .map(|(as_ty, ty)| parse_serde_as(ctx, as_ty, ty))
+            .collect::<TEResult<_>>()?,
+        ))
+      } else {
+        Ok(fallback(ctx, as_ty, ty))
+      };
+    }
+    Type::Path(type_path) => &type_path.path,
+    _ => return Ok(fallback(ctx, as_ty, ty)),
+  };
+
+  let segments = path
+    .segments
+    .iter()
+    .map(|it| it.ident.to_string())
+    .collect::<Vec<_>>();
+  if let Some(mapping) = ctx.config.find_with_mapping(&segments) {
+    return Ok(mapping.into());
+  }
+
+  let args = parse_path_generics(&path.segments.last().unwrap().arguments).unwrap_or_default();
+  let arg = |idx: usize, ty: &TypeInfo| match args.get(idx) {
+    Some(as_ty) => parse_serde_as(ctx, as_ty, ty),
+    None => Ok(ty.clone()),
+  };
+
+  if ctx.config.builtin_types {
+    if let Some(result) = builtin_serde_as(ctx, path, &args, ty) {
+      return result;
+    }
+  }
+
+  // containers, the arguments apply to the elements
+  Ok(match (ty.see_through(), args.len()) {
+    (TypeInfo::Option(ty), 1) => TypeInfo::Option(Box::new(arg(0, ty)?)),
+    (TypeInfo::Vec(ty), 1) => TypeInfo::Vec(Box::new(arg(0, ty)?)),
+    (TypeInfo::Array(ty, len), 1) => TypeInfo::Array(Box::new(arg(0, ty)?), *len),
+    (TypeInfo::Map(ty_k, ty_v), 2) => {
+      TypeInfo::Map(Box::new(arg(0, ty_k)?), Box::new(arg(1, ty_v)?))
+    }
+    (TypeInfo::EsMap(ty_k, ty_v), 2) => {
+      TypeInfo::EsMap(Box::new(arg(0, ty_k)?), Box::new(arg(1, ty_v)?))
+    }
+    _ => fallback(ctx, as_ty, ty),
+  })
+}
+
+/// `as_ty` as a normal type, or `ty` if that's not possible
+fn fallback(ctx: &ParseContext, as_ty: &Type, ty: &TypeInfo) -> TypeInfo {
+  TypeInfo::parse_type(ctx, as_ty, &AttributeInfo::default(), as_ty).unwrap_or_else(|err| {
+    warn!("unable to use the type in serde_as, using the type of the field: {err}");
+    ty.clone()
+  })
+}
+
+/// the json representation of the serde helpers of common crates
+fn builtin_with(path: &[String]) -> Option<TypeInfo> {
+  let option = |ty| TypeInfo::Option(Box::new(ty));
+
+  [
+    ("chrono::serde::ts_seconds", TypeInfo::Number),
+    ("chrono::serde::ts_milliseconds", TypeInfo::Number),
+    ("chrono::serde::ts_microseconds", TypeInfo::Number),
+    ("chrono::serde::ts_nanoseconds", TypeInfo::Number),
+    ("chrono::serde::ts_seconds_option", option(TypeInfo::Number)),
+    (
+      "chrono::serde::ts_milliseconds_option",
+      option(TypeInfo::Number),
+    ),
+    (
+      "chrono::serde::ts_microseconds_option",
+      option(TypeInfo::Number),
+    ),
+    (
+      "chrono::serde::ts_nanoseconds_option",
+      option(TypeInfo::Number),
+    ),
+    ("time::serde::timestamp", TypeInfo::Number),
+    ("time::serde::timestamp::option", option(TypeInfo::Number)),
+    ("time::serde::rfc3339", TypeInfo::String),
+    ("time::serde::rfc3339::option", option(TypeInfo::String)),
+    ("time::serde::rfc2822", TypeInfo::String),
+    ("time::serde::rfc2822::option", option(TypeInfo::String)),
+    ("time::serde::iso8601", TypeInfo::String),
+    ("time::serde::iso8601::option", option(TypeInfo::String)),
+    ("hex", TypeInfo::String),
+    ("hex::serde", TypeInfo::String),
+    ("base64", TypeInfo::String),
+    ("serde_bytes", TypeInfo::Vec(Box::new(TypeInfo::Number))),
+    ("humantime_serde", TypeInfo::String),
+    ("humantime_serde::option", option(TypeInfo::String)),
+    ("serde_with::rust::display_fromstr", TypeInfo::String),
+  ]
+  .into_iter()
+  .find(|(key, _)| match_path(key, path))
+  .map(|(_, ty)| ty)
+}
+
+/// the json representation of the `serde_as` types of serde_with, `None` if it isn't one
+fn builtin_serde_as(
+  ctx: &ParseContext,
+  path: &Path,
+  args: &[Type],
+  ty: &TypeInfo,
+) -> Option<TEResult<TypeInfo>> {
+  let name = path.segments.last()?.ident.to_string();
+
+  // only check the crate if the path is qualified
+  if path.segments.len() > 1 && path.segments.first()?.ident != "serde_with" {
+    return None;
+  }
+
+  Some(Ok(match name.as_str() {
+    "Same" => ty.clone(),
+    "DisplayFromStr" | "NoneAsEmptyString" | "Base64" | "Hex" => TypeInfo::String,
+    "Bytes" | "BytesOrString" => TypeInfo::Vec(Box::new(TypeInfo::Number)),
+    // the format is the first argument, `i64` by default
+    "TimestampSeconds"
+    | "TimestampMilliSeconds"
+    | "TimestampMicroSeconds"
+    | "TimestampNanoSeconds"
+    | "TimestampSecondsWithFrac"
+    | "TimestampMilliSecondsWithFrac"
+    | "TimestampMicroSecondsWithFrac"
+    | "TimestampNanoSecondsWithFrac"
+    | "DurationSeconds"
+    | "DurationMilliSeconds"
+    | "DurationMicroSeconds"
+    | "DurationNanoSeconds"
+    | "DurationSecondsWithFrac"
+    | "DurationMilliSecondsWithFrac"
+    | "DurationMicroSecondsWithFrac"
+    | "DurationNanoSecondsWithFrac" => match args.first() {
+      Some(Type::Path(format)) if format.path.is_ident("String") => TypeInfo::String,
+      _ => TypeInfo::Number,
+    },
+    // the value is converted as the inner `serde_as` type
+    "DefaultOnError" | "DefaultOnNull" => match args.first() {
+      Some(as_ty) => return Some(parse_serde_as(ctx, as_ty, ty)),
+      None => ty.clone(),
+    },
+    // the value is converted through the type in the argument
+    "FromInto" | "TryFromInto" => {
+      let as_ty = args.first()?;
+      return Some(TypeInfo::parse_type(
+        ctx,
+        as_ty,
+        &AttributeInfo::default(),
+        as_ty,
+      ));
+    }
+    _ => return None,
+  }))
+}
@@ -12,6 +12,8 @@ use itertools::Itertools;
 use log::{debug, error, info, warn};
 use syn::Item;
 
+use crate::config::Config;
+use crate::item::attribute_info::Direction;
 use crate::item::item_info::ItemInfo;
 use crate::item::item_parser::ItemParser;
 use crate::ts_compiler::TsCompiler;
@@ -23,6 +25,7 @@ pub struct TypeExporter {
   root: PathBuf,
   output: PathBuf,
 
+  config: Config,
   compiler: TsCompiler,
 
   items: HashMap<UsePath<PathFs>, Vec<TEResult<ItemInfo>>>,
@@ -38,6 +41,7 @@ impl TypeExporter {
     }
 
     Ok(Self {
+      config: Config::load(&root)?,
       root: src_dir.canonicalize()?,
       compiler: TsCompiler::default(),
       output: output.canonicalize()?,
@@ -98,11 +102,12 @@ impl TypeExporter {
       .filter_map(|item| match item {
         Item::Enum(it) => Some(it.ident.to_string()),
         Item::Struct(it) => Some(it.ident.to_string()),
+        Item::Type(it) => Some(it.ident.to_string()),
         _ => None,
       })
       .collect::<Vec<_>>();
 
-    let parser = ItemParser::new(&uses, &local_types);
+    let parser = ItemParser::new(&self.config, &uses, &local_types);
 
     let result = data
       .items
@@ -115,21 +120,35 @@ impl TypeExporter {
 
   pub fn transform_and_write(&self) {
     info!("transform and write");
-    let mut deps = self.transform_and_write_files(self.collect_entries());
+    let mut processed = HashSet::new();
+    let mut deps = self.collect_entries();
 
     while !deps.is_empty() {
-      deps = self.transform_and_write_files(deps);
+      deps = self.transform_and_write_files(deps, &mut processed);
     }
   }
 
-  fn transform_and_write_files(
-    &self,
-    files: HashMap<&UsePath<PathFs>, Vec<&ItemInfo>>,
-  ) -> HashMap<&UsePath<PathFs>, Vec<&ItemInfo>> {
-    files
-      .into_iter()
-      .flat_map(|(path, items)| self.transform_and_write_file(path, items))
-      .collect()
+  /// `processed` is the file and name of the items already written
+  fn transform_and_write_files<'a>(
+    &'a self,
+    files: HashMap<&'a UsePath<PathFs>, Vec<&'a ItemInfo>>,
+    processed: &mut HashSet<(&'a UsePath<PathFs>, &'a str)>,
+  ) -> HashMap<&'a UsePath<PathFs>, Vec<&'a ItemInfo>> {
+    let mut result: HashMap<&UsePath<PathFs>, Vec<&ItemInfo>> = HashMap::new();
+
+    for (path, mut items) in files {
+      // an item can be depended on by several items, only write it once
+      items.retain(|it| processed.insert((path, it.get_name())));
+      if items.is_empty() {
+        continue;
+      }
+
+      for (path, items) in self.transform_and_write_file(path, items) {
+        result.entry(path).or_default().extend(items);
+      }
+    }
+
+    result
   }
 
   fn transform_and_write_file(
@@ -141,6 +160,12 @@ impl TypeExporter {
 
     let mut content_items = vec![];
 
+    let directions = if self.config.split_directions {
+      vec![Direction::Serialize, Direction::Deserialize]
+    } else {
+      vec![Direction::Both]
+    };
+
     for item in items {
       info!(
         "transforming {} in {:?}",
@@ -148,22 +173,32 @@ impl TypeExporter {
         path.to_path_buf_with_ext()
       );
 
-      match item {
-        ItemInfo::Struct { item, processed } => {
-          if *processed {
-            continue;
+      for direction in directions.iter().copied() {
+        match item {
+          ItemInfo::Struct { item, processed } => {
+            if *processed {
+              continue;
+            }
+            let ts_ast = item.to_ts_ast(direction);
+            content_items.push(ts_ast.ast);
+            dependencies.extend(ts_ast.dependencies);
           }
-          let ts_ast = item.to_ts_ast();
-          content_items.push(ts_ast.ast);
-          dependencies.extend(ts_ast.dependencies);
-        }
-        ItemInfo::Enum { item, processed } => {
-          if *processed {
-            continue;
+          ItemInfo::Enum { item, processed } => {
+            if *processed {
+              continue;
+            }
+            let ts_ast = item.to_ts_ast(direction);
+            content_items.extend(ts_ast.ast);
+            dependencies.extend(ts_ast.dependencies);
+          }
+          ItemInfo::Alias { item, processed } => {
+            if *processed {
+              continue;
+            }
+            let ts_ast = item.to_ts_ast(direction);
+            content_items.push(ts_ast.ast);
+            dependencies.extend(ts_ast.dependencies);
           }
-          let ts_ast = item.to_ts_ast();
-          content_items.extend(ts_ast.ast);
-          dependencies.extend(ts_ast.dependencies);
         }
       }
     }
@@ -171,7 +206,25 @@ impl TypeExporter {
     let mut content = dependencies
       .iter()
       .filter(|it| !it.local_use)
-      .map(|it| create_import(&it.relative_from(path)))
+      .map(|it| it.relative_from(path))
+      .flat_map(|it| {
+        // types from ts modules are imported as is
+        if it.module.is_some() {
+          return vec![create_import(&it)];
+        }
+
+        directions
+          .iter()
+          .map(|direction| {
+            create_import(
+              &it
+                .clone()
+                .with_name(direction.type_name(&it.name))
+                .with_actual_name(it.actual_name.as_ref().map(|it| direction.type_name(it))),
+            )
+          })
+          .collect()
+      })
       .collect::<Vec<_>>();
 
     content.extend(content_items);
@@ -180,7 +233,6 @@ impl TypeExporter {
     fs::create_dir_all(output_file.parent().unwrap()).expect("failed to create dir all");
     output_file.set_extension("d.ts");
     fs::OpenOptions::new()
-      .write(true)
       .append(true)
       .create(true)
       .open(output_file)
@@ -190,7 +242,8 @@ impl TypeExporter {
 
     dependencies
       .iter()
-      .group_by(|it| &it.path)
+      .filter(|it| it.module.is_none())
+      .into_group_map_by(|it| &it.path)
       .into_iter()
       .map(|(key, group)| {
         let path = UsePath::<PathFs>::new(key.clone())
@@ -264,14 +317,13 @@ impl TypeExporter {
           .filter(|it| match it {
             ItemInfo::Struct { item, .. } => item.attr.is_entry(),
             ItemInfo::Enum { item, .. } => item.attr.is_entry(),
+            ItemInfo::Alias { item, .. } => item.attr.is_entry(),
           })
           .collect::<Vec<_>>();
         (path, entries)
       })
       .filter(|it| !it.1.is_empty())
-      .map(|it| {
-        let (path, items) = &it;
-
+      .inspect(|(path, items)| {
         for item in items {
           info!(
             "detected entry {}, in {:?}",
@@ -279,8 +331,6 @@ impl TypeExporter {
             path.to_path_buf_with_ext()
           )
         }
-
-        it
       })
       .collect::<HashMap<_, _>>()
   }
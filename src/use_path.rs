@@ -44,6 +44,8 @@ pub struct UsePath<Type = PathRs> {
   pub name: String,
   pub actual_name: Option<String>,
   pub local_use: bool,
+  /// the ts module to import from as is, for types that aren't exported by us
+  pub module: Option<String>,
   pub _type_marker: PhantomData<Type>,
 }
 
@@ -54,6 +56,7 @@ impl UsePath<PathRs> {
       name,
       actual_name,
       local_use: false,
+      module: None,
       _type_marker: Default::default(),
     }
   }
@@ -85,6 +88,11 @@ impl UsePath<PathRs> {
     self
   }
 
+  pub fn with_module(mut self, module: Option<String>) -> Self {
+    self.module = module;
+    self
+  }
+
   pub fn parse_item_use(item_use: &ItemUse) -> Vec<Self> {
     if item_use.leading_colon.is_some() {
       return vec![];
@@ -153,11 +161,16 @@ impl UsePath<PathRs> {
       name: self.name.clone(),
       actual_name: self.actual_name.clone(),
       local_use: self.local_use,
+      module: self.module.clone(),
       _type_marker: Default::default(),
     }
   }
 
   pub fn relative_from(&self, location: &UsePath<PathFs>) -> UsePath<PathRs> {
+    if self.module.is_some() {
+      return self.clone();
+    }
+
     let mut location = location.path.clone();
     location.pop();
 
@@ -221,6 +234,7 @@ impl UsePath<PathFs> {
       name: String::new(),
       actual_name: None,
       local_use: false,
+      module: None,
       _type_marker: Default::default(),
     }
   }
@@ -246,6 +260,7 @@ impl UsePath<PathFs> {
       name: self.name.clone(),
       actual_name: self.actual_name.clone(),
       local_use: self.local_use,
+      module: self.module.clone(),
       _type_marker: Default::default(),
     }
   }
@@ -271,7 +286,8 @@ impl<P: AsRef<Path>> From<P> for UsePath<PathFs> {
       name: String::new(),
       actual_name: None,
       local_use: false,
-      _type_marker: PhantomData::default(),
+      module: None,
+      _type_marker: PhantomData,
     }
   }
 }
@@ -3,24 +3,50 @@
  * SPDX-License-Identifier: AGPL-3.0-only
  */
 
-use crate::item::attribute_info::{AttributeInfo, RenameAll};
+use crate::item::attribute_info::{AttributeInfo, Direction, RenameAll};
 
 pub mod ts_ast_utils;
 
+/// whether `path` is written as a suffix of the full path `key`, e.g. `["money", "Money"]` and
+/// `"foreign::money::Money"`
+pub fn match_path(key: &str, path: &[String]) -> bool {
+  let key = key.split("::").collect::<Vec<_>>();
+  key.len() >= path.len() && key.iter().rev().zip(path.iter().rev()).all(|(a, b)| a == b)
+}
+
+/// the name of an item or a field
 pub fn rename_name(
   attr: &AttributeInfo,
   rename_all: Option<&RenameAll>,
   name: Option<&String>,
+  direction: Direction,
 ) -> Option<String> {
-  if let Some(rename) = attr.rename.clone().into() {
-    return Some(rename);
+  if let Some(rename) = attr.rename(direction) {
+    return Some(rename.clone());
   }
 
   name.cloned().map(|name| {
     if let Some(rename_all) = rename_all {
-      rename_all.do_convert(name)
+      rename_all.apply_to_field(&name)
     } else {
       name
     }
   })
 }
+
+/// the name of a variant
+pub fn rename_variant(
+  attr: &AttributeInfo,
+  rename_all: Option<&RenameAll>,
+  name: &str,
+  direction: Direction,
+) -> String {
+  if let Some(rename) = attr.rename(direction) {
+    return rename.clone();
+  }
+
+  match rename_all {
+    Some(rename_all) => rename_all.apply_to_variant(name),
+    None => name.to_string(),
+  }
+}
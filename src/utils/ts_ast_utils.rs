@@ -18,6 +18,41 @@ pub fn create_type_array(types: Vec<Box<ast::TsType>>) -> ast::TsType {
   ))
 }
 
+pub fn create_type_paren(ty: ast::TsType) -> ast::TsType {
+  ast::TsType::TsParenthesizedType(ast::TsParenthesizedType {
+    span: Default::default(),
+    type_ann: Box::new(ty),
+  })
+}
+
+/// `(string & {})`, any string without losing the completion of the literals in the same union
+pub fn create_open_type(kind: ast::TsKeywordTypeKind) -> ast::TsType {
+  create_type_paren(create_type_intersection(vec![
+    Box::new(create_keyword_type(kind)),
+    Box::new(create_type_lit(vec![])),
+  ]))
+}
+
+/// unions inside are parenthesized
+pub fn create_type_intersection(types: Vec<Box<ast::TsType>>) -> ast::TsType {
+  let types = types
+    .into_iter()
+    .map(|it| match *it {
+      ast::TsType::TsUnionOrIntersectionType(ast::TsUnionOrIntersectionType::TsUnionType(_)) => {
+        Box::new(create_type_paren(*it))
+      }
+      _ => it,
+    })
+    .collect();
+
+  ast::TsType::TsUnionOrIntersectionType(ast::TsUnionOrIntersectionType::TsIntersectionType(
+    ast::TsIntersectionType {
+      span: Default::default(),
+      types,
+    },
+  ))
+}
+
 pub fn create_type_ref<T: AsRef<str>>(ty: T, params: Option<Vec<Box<ast::TsType>>>) -> ast::TsType {
   ast::TsType::TsTypeRef(ast::TsTypeRef {
     span: Default::default(),
@@ -34,6 +69,20 @@ pub fn create_type_ref<T: AsRef<str>>(ty: T, params: Option<Vec<Box<ast::TsType>
   })
 }
 
+pub fn create_type_tuple(types: Vec<Box<ast::TsType>>) -> ast::TsType {
+  ast::TsType::TsTupleType(ast::TsTupleType {
+    span: Default::default(),
+    elem_types: types
+      .into_iter()
+      .map(|it| ast::TsTupleElement {
+        span: Default::default(),
+        label: None,
+        ty: it,
+      })
+      .collect(),
+  })
+}
+
 pub fn create_keyword_type(type_kind: ast::TsKeywordTypeKind) -> ast::TsType {
   ast::TsType::TsKeywordType(ast::TsKeywordType {
     span: Default::default(),
@@ -53,6 +102,23 @@ pub fn create_expr_str<S: AsRef<str>>(ident: S) -> ast::Expr {
   ast::Expr::Lit(ast::Lit::Str(Str::from(ident.as_ref())))
 }
 
+/// a property key, quoted if it isn't a valid identifier, e.g. `kebab-case`
+pub fn create_expr_key<S: AsRef<str>>(key: S) -> ast::Expr {
+  let key = key.as_ref();
+  let mut chars = key.chars();
+
+  let is_ident = chars
+    .next()
+    .is_some_and(|it| it.is_alphabetic() || it == '_' || it == '$')
+    && chars.all(|it| it.is_alphanumeric() || it == '_' || it == '$');
+
+  if is_ident {
+    create_expr_ident(key)
+  } else {
+    create_expr_str(key)
+  }
+}
+
 pub fn create_module_decl_item(decl: ast::Decl) -> ast::ModuleItem {
   ast::ModuleItem::ModuleDecl(ast::ModuleDecl::ExportDecl(ast::ExportDecl {
     span: Default::default(),
@@ -84,6 +150,35 @@ pub fn create_property_type_element(
   })
 }
 
+/// `{ [key: string]: T }`
+pub fn create_index_signature_type_lit(type_ann: ast::TsTypeAnn) -> ast::TsType {
+  create_type_lit(vec![ast::TsTypeElement::TsIndexSignature(
+    ast::TsIndexSignature {
+      params: vec![ast::TsFnParam::Ident(ast::BindingIdent {
+        id: create_ident("key"),
+        type_ann: Some(Box::new(type_to_type_ann(create_keyword_type(
+          ast::TsKeywordTypeKind::TsStringKeyword,
+        )))),
+      })],
+      type_ann: Some(Box::new(type_ann)),
+      readonly: false,
+      is_static: false,
+      span: Default::default(),
+    },
+  )])
+}
+
+pub fn create_optional_property_type_element(
+  ident: ast::Expr,
+  type_ann: ast::TsTypeAnn,
+) -> ast::TsTypeElement {
+  let mut element = create_property_type_element(ident, type_ann);
+  if let ast::TsTypeElement::TsPropertySignature(it) = &mut element {
+    it.optional = true;
+  }
+  element
+}
+
 pub fn type_to_type_ann(ts_type: ast::TsType) -> ast::TsTypeAnn {
   ast::TsTypeAnn {
     span: Default::default(),
@@ -98,12 +193,100 @@ pub fn create_str_lit_type<S: AsRef<str>>(string: S) -> ast::TsType {
   })
 }
 
-pub fn create_type_alias_decl(id: ast::Ident, ts_type: ast::TsType) -> ast::Decl {
+pub fn create_number_lit_type(value: i128) -> ast::TsType {
+  ast::TsType::TsLitType(ast::TsLitType {
+    span: Default::default(),
+    lit: ast::TsLit::Number(ast::Number::from(value as f64)),
+  })
+}
+
+pub fn create_expr_number(value: i128) -> ast::Expr {
+  let number = ast::Expr::Lit(ast::Lit::Num(
+    ast::Number::from(value.unsigned_abs() as f64),
+  ));
+
+  if value < 0 {
+    ast::Expr::Unary(ast::UnaryExpr {
+      span: Default::default(),
+      op: ast::UnaryOp::Minus,
+      arg: Box::new(number),
+    })
+  } else {
+    number
+  }
+}
+
+/// `members` are the names and the values
+pub fn create_enum_decl(id: ast::Ident, is_const: bool, members: Vec<(String, i128)>) -> ast::Decl {
+  ast::Decl::TsEnum(Box::new(ast::TsEnumDecl {
+    span: Default::default(),
+    declare: true,
+    is_const,
+    id,
+    members: members
+      .into_iter()
+      .map(|(name, value)| ast::TsEnumMember {
+        span: Default::default(),
+        id: ast::TsEnumMemberId::Ident(create_ident(name)),
+        init: Some(Box::new(create_expr_number(value))),
+      })
+      .collect(),
+  }))
+}
+
+pub fn create_type_param_decl<S: AsRef<str>>(params: &[S]) -> Option<Box<ast::TsTypeParamDecl>> {
+  if params.is_empty() {
+    return None;
+  }
+
+  Some(Box::new(ast::TsTypeParamDecl {
+    span: Default::default(),
+    params: params
+      .iter()
+      .map(|it| ast::TsTypeParam {
+        span: Default::default(),
+        name: create_ident(it),
+        is_in: false,
+        is_out: false,
+        is_const: false,
+        constraint: None,
+        default: None,
+      })
+      .collect(),
+  }))
+}
+
+/// `${number}`
+pub fn create_number_str_type() -> ast::TsType {
+  let quasi = |tail| ast::TplElement {
+    span: Default::default(),
+    tail,
+    cooked: None,
+    raw: "".into(),
+  };
+
+  ast::TsType::TsLitType(ast::TsLitType {
+    span: Default::default(),
+    lit: ast::TsLit::Tpl(ast::TsTplLitType {
+      span: Default::default(),
+      types: vec![Box::new(create_keyword_type(
+        ast::TsKeywordTypeKind::TsNumberKeyword,
+      ))],
+      quasis: vec![quasi(false), quasi(true)],
+    }),
+  })
+}
+
+pub fn create_type_alias_decl(
+  id: ast::Ident,
+  type_params: Option<Box<ast::TsTypeParamDecl>>,
+  ts_type: ast::TsType,
+) -> ast::Decl {
   ast::Decl::TsTypeAlias(Box::new(ast::TsTypeAliasDecl {
     span: Default::default(),
     declare: true,
     id,
-    type_params: None,
+    type_params,
     type_ann: Box::new(ts_type),
   }))
 }
@@ -120,9 +303,10 @@ pub fn create_import(path: &UsePath<PathRs>) -> ast::ModuleItem {
         .map(|it| ast::ModuleExportName::Ident(create_ident(it))),
       is_type_only: false,
     })],
-    src: Box::new(ast::Str::from(
-      PathBuf::from(path.to_fs()).to_string_lossy(),
-    )),
+    src: Box::new(match &path.module {
+      Some(module) => ast::Str::from(module.as_str()),
+      None => ast::Str::from(PathBuf::from(path.to_fs()).to_string_lossy()),
+    }),
     type_only: true,
     asserts: None,
   }))